@@ -8,7 +8,6 @@ cp chromedriver_PATCHED checker/ && \
 cp chromedriver checker/ && \
 cp app-config.yaml checker/ && \
 cp .env checker/ && \
-cp -r migrations checker/ && \
 cp target/release/fiverr-message-checker checker/app && \
 tar -cvzf checker.tar.gz checker && \
 rm -rf checker
\ No newline at end of file
@@ -0,0 +1,4 @@
+fn main() {
+    // Rebuild when a migration is added so `sqlx::migrate!` embeds it.
+    println!("cargo:rerun-if-changed=migrations");
+}
@@ -0,0 +1,8 @@
+-- Record the target each gig was scraped from.
+ALTER TABLE gigs ADD COLUMN category TEXT;
+ALTER TABLE gigs ADD COLUMN menu TEXT;
+ALTER TABLE gigs ADD COLUMN search_query TEXT NOT NULL DEFAULT '';
+
+-- Gigs scraped before targets were configurable all came from the
+-- previously hard-coded target.
+UPDATE gigs SET category = 'programming-tech', menu = 'business';
@@ -0,0 +1,17 @@
+-- Track how far the listing of each scrape target has been worked through.
+CREATE TABLE scrape_progress (
+    category TEXT NOT NULL,
+    menu TEXT NOT NULL,
+    search_query TEXT NOT NULL DEFAULT '',
+    last_page BIGINT NOT NULL DEFAULT 1,
+    last_gig_index BIGINT,
+    page_finished_at TIMESTAMP,
+    PRIMARY KEY (category, menu, search_query)
+);
+
+-- Seed the progress of existing targets from the gigs scraped so far.
+INSERT INTO scrape_progress(category, menu, last_page)
+SELECT category, menu, MAX(page)
+FROM gigs
+WHERE category IS NOT NULL AND menu IS NOT NULL
+GROUP BY category, menu;
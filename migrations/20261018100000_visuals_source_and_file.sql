@@ -0,0 +1,25 @@
+-- Rebuild the visuals table so `gig_id` matches the text key of `gigs` and
+-- both the source URL and the downloaded file are recorded.
+CREATE TABLE visuals_new (
+    id VARCHAR(100) PRIMARY KEY NOT NULL,
+    gig_id VARCHAR(100) NOT NULL,
+    visual_type TEXT NOT NULL,
+    position BIGINT,
+    source_url TEXT,
+    file_path TEXT,
+    mime_type TEXT,
+    byte_size BIGINT,
+    sha256 TEXT,
+    downloaded_at TIMESTAMP,
+    error TEXT,
+    FOREIGN KEY (gig_id) REFERENCES gigs(id) ON DELETE CASCADE
+);
+
+-- Visuals saved so far only kept the path of the downloaded file.
+INSERT INTO visuals_new(id, gig_id, visual_type, file_path)
+SELECT id, CAST(gig_id AS TEXT), visual_type, file_path FROM visuals;
+
+DROP TABLE visuals;
+ALTER TABLE visuals_new RENAME TO visuals;
+
+CREATE INDEX visuals_gig_id ON visuals(gig_id);
@@ -0,0 +1,2 @@
+-- Gigs marked 'incomplete' are scraped again.
+ALTER TABLE gigs ADD COLUMN status TEXT NOT NULL DEFAULT 'complete';
@@ -0,0 +1,27 @@
+-- Create the gig packages table (Basic/Standard/Premium tiers).
+CREATE TABLE gig_packages (
+    id VARCHAR(100) PRIMARY KEY NOT NULL,
+    gig_id VARCHAR(100) NOT NULL,
+    position BIGINT NOT NULL,
+    tier TEXT NOT NULL,
+    title TEXT,
+    description TEXT,
+    price REAL,
+    currency TEXT,
+    delivery_days BIGINT,
+    revisions BIGINT,
+    unlimited_revisions BOOLEAN NOT NULL DEFAULT FALSE,
+    FOREIGN KEY (gig_id) REFERENCES gigs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX gig_packages_gig_id ON gig_packages(gig_id);
+
+-- Create the package feature checklist table.
+CREATE TABLE gig_package_features (
+    package_id VARCHAR(100) NOT NULL,
+    position BIGINT NOT NULL,
+    feature TEXT NOT NULL,
+    included BOOLEAN NOT NULL,
+    PRIMARY KEY (package_id, position),
+    FOREIGN KEY (package_id) REFERENCES gig_packages(id) ON DELETE CASCADE
+);
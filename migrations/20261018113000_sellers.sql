@@ -0,0 +1,17 @@
+-- Create the sellers table; gigs reference the seller that offers them.
+CREATE TABLE sellers (
+    id VARCHAR(100) PRIMARY KEY NOT NULL,
+    username TEXT NOT NULL UNIQUE,
+    display_name TEXT,
+    level TEXT,
+    country TEXT,
+    member_since TEXT,
+    avg_response_time TEXT,
+    last_delivery TEXT,
+    languages TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE gigs ADD COLUMN seller_id VARCHAR(100) REFERENCES sellers(id);
+
+CREATE INDEX gigs_seller_id ON gigs(seller_id);
@@ -0,0 +1,20 @@
+-- Record the listing card of every gig seen on a listing, including gigs
+-- that are filtered out or were scraped before, so its rank can be followed
+-- over time.
+CREATE TABLE gig_listings (
+    id VARCHAR(100) PRIMARY KEY NOT NULL,
+    url TEXT NOT NULL,
+    category TEXT NOT NULL,
+    menu TEXT NOT NULL,
+    search_query TEXT NOT NULL DEFAULT '',
+    page BIGINT NOT NULL,
+    position BIGINT NOT NULL,
+    rating REAL,
+    review_count BIGINT,
+    starting_price REAL,
+    currency TEXT,
+    badges TEXT,
+    seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX gig_listings_url ON gig_listings(url);
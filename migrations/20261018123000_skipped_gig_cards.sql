@@ -0,0 +1,11 @@
+-- Record why gig cards of a listing were not scraped.
+CREATE TABLE skipped_gig_cards (
+    url TEXT PRIMARY KEY NOT NULL,
+    category TEXT NOT NULL,
+    menu TEXT NOT NULL,
+    search_query TEXT NOT NULL DEFAULT '',
+    page BIGINT NOT NULL,
+    position BIGINT NOT NULL,
+    reason TEXT NOT NULL,
+    skipped_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
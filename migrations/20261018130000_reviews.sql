@@ -0,0 +1,20 @@
+-- Create the reviews table. Reviews are keyed per gig by a hash of the
+-- reviewer, country, text and order of occurrence of identical reviews, so
+-- re-scrapes update them in place.
+CREATE TABLE reviews (
+    id VARCHAR(100) PRIMARY KEY NOT NULL,
+    gig_id VARCHAR(100) NOT NULL,
+    review_key TEXT NOT NULL,
+    reviewer TEXT,
+    country TEXT,
+    rating REAL,
+    review_date TEXT,
+    text TEXT NOT NULL,
+    price_range TEXT,
+    duration TEXT,
+    seller_response TEXT,
+    first_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (gig_id, review_key),
+    FOREIGN KEY (gig_id) REFERENCES gigs(id) ON DELETE CASCADE
+);
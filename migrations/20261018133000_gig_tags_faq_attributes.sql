@@ -0,0 +1,39 @@
+-- Create the gig search tags table.
+CREATE TABLE gig_tags (
+    gig_id VARCHAR(100) NOT NULL,
+    position BIGINT NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (gig_id, position),
+    FOREIGN KEY (gig_id) REFERENCES gigs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX gig_tags_tag ON gig_tags(tag);
+
+-- How many gigs and sellers use each tag.
+CREATE VIEW tag_frequency AS
+SELECT
+    lower(gig_tags.tag) AS tag,
+    COUNT(DISTINCT gig_tags.gig_id) AS gig_count,
+    COUNT(DISTINCT gigs.seller_id) AS seller_count
+FROM gig_tags
+JOIN gigs ON gigs.id = gig_tags.gig_id
+GROUP BY lower(gig_tags.tag);
+
+-- Create the gig FAQ table.
+CREATE TABLE gig_faq (
+    gig_id VARCHAR(100) NOT NULL,
+    position BIGINT NOT NULL,
+    question TEXT NOT NULL,
+    answer TEXT,
+    PRIMARY KEY (gig_id, position),
+    FOREIGN KEY (gig_id) REFERENCES gigs(id) ON DELETE CASCADE
+);
+
+-- Create the gig metadata attributes table, e.g. "Programming language".
+CREATE TABLE gig_attributes (
+    gig_id VARCHAR(100) NOT NULL,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (gig_id, name, value),
+    FOREIGN KEY (gig_id) REFERENCES gigs(id) ON DELETE CASCADE
+);
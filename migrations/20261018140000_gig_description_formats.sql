@@ -0,0 +1,4 @@
+-- `description` keeps the raw HTML; these hold the normalized plain text and
+-- Markdown renderings of it.
+ALTER TABLE gigs ADD COLUMN description_text TEXT;
+ALTER TABLE gigs ADD COLUMN description_markdown TEXT;
@@ -0,0 +1,5 @@
+-- Record the HTTP status of failed media downloads so that permanent failures
+-- can be told apart from transient ones.
+ALTER TABLE visuals ADD COLUMN http_status BIGINT;
+
+CREATE INDEX visuals_failed ON visuals(gig_id) WHERE file_path IS NULL AND error IS NOT NULL;
@@ -0,0 +1,6 @@
+-- Visuals refer to their file by a storage URI, so that files can be kept in
+-- other stores than the local download directory.
+ALTER TABLE visuals RENAME COLUMN file_path TO storage_uri;
+
+-- Files downloaded so far are in the local download directory.
+UPDATE visuals SET storage_uri = 'file://' || storage_uri WHERE storage_uri IS NOT NULL;
@@ -0,0 +1,6 @@
+-- Metadata of downloaded videos and the storage URI of their poster frame.
+ALTER TABLE visuals ADD COLUMN duration_secs REAL;
+ALTER TABLE visuals ADD COLUMN width BIGINT;
+ALTER TABLE visuals ADD COLUMN height BIGINT;
+ALTER TABLE visuals ADD COLUMN video_codec TEXT;
+ALTER TABLE visuals ADD COLUMN poster_uri TEXT;
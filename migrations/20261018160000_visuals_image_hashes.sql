@@ -0,0 +1,5 @@
+-- Perceptual hashes of downloaded images, as 16 hex digits, for finding
+-- near-duplicate images across gigs.
+ALTER TABLE visuals ADD COLUMN ahash TEXT;
+ALTER TABLE visuals ADD COLUMN dhash TEXT;
+ALTER TABLE visuals ADD COLUMN phash TEXT;
@@ -0,0 +1,18 @@
+-- Gigs whose scraping failed, so that a gig failing on every visit is
+-- quarantined instead of being picked again forever. The listing a gig was
+-- found on is kept so that it can be scraped again by its URL until it is
+-- quarantined.
+CREATE TABLE failed_gigs (
+    url TEXT PRIMARY KEY NOT NULL,
+    attempts BIGINT NOT NULL,
+    last_error TEXT NOT NULL,
+    -- The selector of the element that was missing, if that was the failure.
+    selector TEXT,
+    category TEXT NOT NULL,
+    menu TEXT NOT NULL,
+    search_query TEXT NOT NULL DEFAULT '',
+    page BIGINT NOT NULL,
+    first_failed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_failed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    quarantined_at TIMESTAMP
+);
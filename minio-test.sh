@@ -0,0 +1,22 @@
+#!/bin/bash
+
+# Starts a throwaway MinIO server in Docker and runs the ignored S3 media store test
+# against it. Needs Docker and a DATABASE_URL for building (see README.md).
+
+container=media-store-minio-test
+export MINIO_TEST_ENDPOINT=http://localhost:9000
+export MINIO_TEST_BUCKET=media-store-test
+export MINIO_TEST_ACCESS_KEY_ID=minioadmin
+export MINIO_TEST_SECRET_ACCESS_KEY=minioadmin
+
+docker run -d --rm --name "$container" -p 9000:9000 \
+    -e MINIO_ROOT_USER="$MINIO_TEST_ACCESS_KEY_ID" \
+    -e MINIO_ROOT_PASSWORD="$MINIO_TEST_SECRET_ACCESS_KEY" \
+    minio/minio server /data || exit 1
+trap 'docker stop "$container" > /dev/null' EXIT
+
+until curl -sf "$MINIO_TEST_ENDPOINT/minio/health/ready"; do sleep 1; done
+docker exec "$container" mc alias set local http://localhost:9000 \
+    "$MINIO_TEST_ACCESS_KEY_ID" "$MINIO_TEST_SECRET_ACCESS_KEY" > /dev/null && \
+docker exec "$container" mc mb "local/$MINIO_TEST_BUCKET" && \
+cargo test s3_store_round_trip -- --ignored
@@ -1,5 +0,0 @@
-#!/bin/bash
-while true; do
-    clear && cargo run
-    sleep 1
-done
\ No newline at end of file
@@ -6,4 +6,234 @@ pub struct AppConfig {
     pub log_level: String,
     pub database_url: String,
     pub download_dir: String,
+    #[serde(default = "default_scrape_targets")]
+    pub scrape_targets: Vec<ScrapeTarget>,
+    #[serde(default)]
+    pub target_rotation: TargetRotation,
+    #[serde(default)]
+    pub gig_filter: GigFilterConfig,
+    /// The maximum number of reviews collected per gig.
+    #[serde(default = "default_review_limit")]
+    pub review_limit: usize,
+    /// The failed attempts after which a gig is quarantined and no longer picked.
+    #[serde(default = "default_quarantine_after")]
+    pub quarantine_after: u32,
+    #[serde(default)]
+    pub media_capture: MediaCapture,
+    #[serde(default)]
+    pub media_download: MediaDownloadConfig,
+    #[serde(default)]
+    pub media_store: MediaStoreConfig,
+    #[serde(default)]
+    pub video_processing: VideoProcessingConfig,
+    #[serde(default)]
+    pub supervisor: SupervisorConfig,
+}
+
+/// A gig listing to scrape: either a category/menu pair in the Fiverr categories menu or the
+/// results of a search query.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScrapeTarget {
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub menu: String,
+    #[serde(default)]
+    pub search_query: Option<String>,
+}
+
+impl ScrapeTarget {
+    pub fn is_valid(&self) -> bool {
+        self.search_query.is_some() || !(self.category.is_empty() || self.menu.is_empty())
+    }
+
+    /// The search query as stored in the `scrape_progress` key, empty for menu targets.
+    pub fn search_query_key(&self) -> &str {
+        self.search_query.as_deref().unwrap_or("")
+    }
+}
+
+impl std::fmt::Display for ScrapeTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.search_query {
+            Some(query) => write!(f, "search:{query}"),
+            None => write!(f, "{}/{}", self.category, self.menu),
+        }
+    }
+}
+
+/// How the main loop moves between the configured scrape targets.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetRotation {
+    /// Stay on a target until its listing runs out of pages, then move to the next one.
+    #[default]
+    Sequential,
+    /// Scrape one gig from each target in turn.
+    RoundRobin,
+}
+
+/// How the URLs of a gig's gallery media are collected.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaCapture {
+    /// Click through the gallery and read the source of each slide.
+    #[default]
+    Gallery,
+    /// Record the media responses of Fiverr's CDN while the gig page loads.
+    Network,
+    /// Like `network`, but also walk the gallery when fewer media responses were recorded than
+    /// the gallery has slides, keeping the recorded media the walk misses.
+    NetworkWithGalleryFallback,
+}
+
+/// Limits of the gig media downloads.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct MediaDownloadConfig {
+    /// How many files of a gig are downloaded at the same time.
+    pub concurrency: usize,
+    /// Files larger than this are not downloaded. Unset allows any size.
+    pub max_file_size_mb: Option<u64>,
+    /// The time a single download may take, including reading the whole body.
+    pub timeout_secs: u64,
+    /// How often a download is tried before giving up on transient failures such as 5xx
+    /// responses, timeouts and dropped connections.
+    pub max_attempts: u32,
+    /// The wait before the first retry, doubled for every further retry up to five minutes.
+    pub retry_delay_ms: u64,
+}
+
+impl Default for MediaDownloadConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            max_file_size_mb: None,
+            timeout_secs: 600,
+            max_attempts: 4,
+            retry_delay_ms: 1000,
+        }
+    }
+}
+
+/// Where downloaded media files are kept.
+#[derive(Debug, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MediaStoreConfig {
+    /// In `download_dir`.
+    #[default]
+    Local,
+    /// In a bucket of S3 or an S3-compatible service; `download_dir` only holds the files being
+    /// downloaded.
+    S3(S3StoreConfig),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct S3StoreConfig {
+    pub bucket: String,
+    /// The key prefix under which files are stored.
+    #[serde(default)]
+    pub prefix: String,
+    /// The endpoint of S3-compatible services, e.g. `http://localhost:9000` for MinIO.
+    pub endpoint: Option<String>,
+    pub region: Option<String>,
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<String>,
+    /// Allows plain HTTP endpoints.
+    #[serde(default)]
+    pub allow_http: bool,
+}
+
+/// Probing downloaded videos and extracting their poster frames with ffprobe and ffmpeg.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct VideoProcessingConfig {
+    pub enabled: bool,
+    pub ffprobe_path: String,
+    pub ffmpeg_path: String,
+    /// The offset of the poster frame into the video.
+    pub poster_at_secs: f64,
+}
+
+impl Default for VideoProcessingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ffprobe_path: "ffprobe".to_string(),
+            ffmpeg_path: "ffmpeg".to_string(),
+            poster_at_secs: 1.0,
+        }
+    }
+}
+
+/// How the scraper recovers from failed browser sessions without leaving the process.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SupervisorConfig {
+    /// The scraper stops after this many failed sessions without a gig scraped in between.
+    pub max_consecutive_failures: u32,
+    /// The wait before the first restart, doubled for every further failure in a row.
+    pub initial_backoff_secs: u64,
+    pub max_backoff_secs: u64,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            max_consecutive_failures: 10,
+            initial_backoff_secs: 5,
+            max_backoff_secs: 600,
+        }
+    }
+}
+
+fn default_review_limit() -> usize {
+    50
+}
+
+fn default_quarantine_after() -> u32 {
+    3
+}
+
+fn default_scrape_targets() -> Vec<ScrapeTarget> {
+    vec![ScrapeTarget {
+        category: "programming-tech".to_string(),
+        menu: "business".to_string(),
+        search_query: None,
+    }]
+}
+
+/// Which gig cards of a listing are worth scraping. Unset bounds are not checked; a card missing
+/// a value that a bound checks is skipped.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct GigFilterConfig {
+    /// Cards whose review count is missing or cannot be parsed (e.g. a new format) are skipped
+    /// while either review bound is set, so the default skips them too.
+    pub min_reviews: Option<u32>,
+    pub max_reviews: Option<u32>,
+    pub min_rating: Option<f64>,
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    /// Seller levels as shown on the card, e.g. "Level 2" or "Top Rated". Empty allows any level.
+    pub seller_levels: Vec<String>,
+    /// Regexes of which at least one has to match the card title. Empty allows any title.
+    pub title_include: Vec<String>,
+    /// Regexes of which none may match the card title.
+    pub title_exclude: Vec<String>,
+}
+
+impl Default for GigFilterConfig {
+    fn default() -> Self {
+        Self {
+            min_reviews: Some(100),
+            max_reviews: None,
+            min_rating: None,
+            min_price: None,
+            max_price: None,
+            seller_levels: Vec::new(),
+            title_include: Vec::new(),
+            title_exclude: Vec::new(),
+        }
+    }
 }
@@ -0,0 +1,96 @@
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[command(about = "Scrapes Fiverr gig listings into a SQLite database")]
+pub struct Cli {
+    /// Runs the scraper when no command is given.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Inspects or applies the embedded database migrations.
+    Migrate {
+        #[command(subcommand)]
+        action: MigrateCommand,
+    },
+    /// Manages scraped gigs.
+    Gigs {
+        #[command(subcommand)]
+        action: GigsCommand,
+    },
+    /// Manages downloaded media files.
+    Media {
+        #[command(subcommand)]
+        action: MediaCommand,
+    },
+    /// Queries the downloaded gig visuals.
+    Visuals {
+        #[command(subcommand)]
+        action: VisualsCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MigrateCommand {
+    /// Lists the embedded migrations and whether each has been applied.
+    Status,
+    /// Applies all pending migrations.
+    Up,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GigsCommand {
+    /// Marks a scraped gig as incomplete so that the scraper visits it again.
+    MarkIncomplete {
+        /// The gig URL; query parameters are ignored.
+        url: String,
+    },
+    /// Fills in the plain text and Markdown descriptions of gigs saved without them.
+    NormalizeDescriptions,
+    /// Lists the gigs that failed to scrape, including the quarantined ones.
+    Failed,
+    /// Releases failed gigs from quarantine and resets their failure count.
+    Requeue {
+        /// The gig URL; query parameters are ignored.
+        #[arg(required_unless_present = "all")]
+        url: Option<String>,
+        /// Requeues all failed gigs.
+        #[arg(long, conflicts_with = "url")]
+        all: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MediaCommand {
+    /// Deletes files in the download directory that no visual references. Do not run this while
+    /// the scraper is running, as files of gigs still being saved are not referenced yet.
+    Gc {
+        /// Lists the files that would be deleted without deleting them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Downloads the visuals whose download failed earlier again from their source URLs.
+    RetryFailed,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum VisualsCommand {
+    /// Computes the perceptual hashes of downloaded images that have none yet.
+    Hash,
+    /// Lists the images that look like the image of a visual.
+    Similar {
+        /// The visual ID.
+        id: String,
+        /// The largest pHash Hamming distance (0-64) of images considered alike.
+        #[arg(long, default_value_t = 10)]
+        max_distance: u32,
+    },
+    /// Lists the groups of near-identical images that appear in more than one gig.
+    Clusters {
+        /// The largest pHash Hamming distance (0-64) of images considered alike.
+        #[arg(long, default_value_t = 6)]
+        max_distance: u32,
+    },
+}
@@ -0,0 +1,203 @@
+use headless_chrome::{
+    browser::{ConnectionClosed, tab::NoElementFound},
+    util::Timeout,
+};
+use thiserror::Error;
+
+/// The failures of scraping steps that the main loop tells apart.
+#[derive(Debug, Error)]
+pub enum ScrapeError {
+    #[error("Could not find element ({selector}) {context}")]
+    SelectorNotFound { selector: String, context: String },
+    #[error("Element ({selector}) does not have a '{attribute}' attribute")]
+    AttributeMissing { selector: String, attribute: String },
+    #[error("Timed out navigating to {page}: {message}")]
+    NavigationTimeout { page: String, message: String },
+    #[error("Still on an error page after {reloads} reloads: {url}")]
+    BlockedByBotProtection { url: String, reloads: u32 },
+    #[error("The listing has no page {page}")]
+    PaginationExhausted { page: u32 },
+    #[error("Could not save to the database: {0}")]
+    StoreError(anyhow::Error),
+    #[error("Could not download {url}: {message}")]
+    DownloadError { url: String, message: String },
+}
+
+/// How the main loop goes on after a failed step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// Try the same step again.
+    Retry,
+    /// Pass over the gig being scraped.
+    SkipGig,
+    /// Move on to the next scrape target.
+    SwitchTarget,
+    /// Stop the scraper.
+    Stop,
+}
+
+impl ScrapeError {
+    /// Maps the browser's failure to find the element, whether at once or after waiting, to
+    /// [`ScrapeError::SelectorNotFound`], keeping other errors as they are.
+    pub fn not_found(selector: &str, context: &str) -> impl FnOnce(anyhow::Error) -> anyhow::Error {
+        let (selector, context) = (selector.to_owned(), context.to_owned());
+        move |error| match error.is::<NoElementFound>() || error.is::<Timeout>() {
+            true => ScrapeError::SelectorNotFound { selector, context }.into(),
+            false => error,
+        }
+    }
+
+    pub fn action(&self) -> ErrorAction {
+        match self {
+            ScrapeError::SelectorNotFound { .. }
+            | ScrapeError::AttributeMissing { .. }
+            | ScrapeError::DownloadError { .. } => ErrorAction::SkipGig,
+            ScrapeError::NavigationTimeout { .. } => ErrorAction::Retry,
+            ScrapeError::PaginationExhausted { .. } => ErrorAction::SwitchTarget,
+            ScrapeError::BlockedByBotProtection { .. } | ScrapeError::StoreError(_) => {
+                ErrorAction::Stop
+            }
+        }
+    }
+}
+
+/// The selector of the element whose absence caused the error, if any.
+pub fn selector_of(error: &anyhow::Error) -> Option<&str> {
+    match error.downcast_ref::<ScrapeError>()? {
+        ScrapeError::SelectorNotFound { selector, .. }
+        | ScrapeError::AttributeMissing { selector, .. } => Some(selector),
+        _ => None,
+    }
+}
+
+/// The action for any error of a scraping step. Errors of the browser that are not wrapped in a
+/// [`ScrapeError`] are treated like the variant they correspond to; all others stop the scraper.
+pub fn action_for(error: &anyhow::Error) -> ErrorAction {
+    if let Some(error) = error.downcast_ref::<ScrapeError>() {
+        error.action()
+    } else if error.is::<Timeout>() {
+        ErrorAction::Retry
+    } else if error.is::<NoElementFound>() {
+        ErrorAction::SkipGig
+    } else {
+        ErrorAction::Stop
+    }
+}
+
+/// Whether a new browser session may get past the error that ended the previous one. Database
+/// failures are not fixed by reconnecting the browser.
+pub fn is_restartable(error: &anyhow::Error) -> bool {
+    let store_error = matches!(
+        error.downcast_ref::<ScrapeError>(),
+        Some(ScrapeError::StoreError(_))
+    );
+    !(store_error || error.is::<sqlx::Error>())
+}
+
+/// Whether the gig being scraped is to blame for the error, rather than the browser, the
+/// database or bot protection. Transient failures, which are retried, are not blamed on the gig
+/// either.
+pub fn is_caused_by_gig(error: &anyhow::Error) -> bool {
+    let blocked = matches!(
+        error.downcast_ref::<ScrapeError>(),
+        Some(ScrapeError::BlockedByBotProtection { .. })
+    );
+    !blocked
+        && action_for(error) != ErrorAction::Retry
+        && is_restartable(error)
+        && !is_connection_lost(error)
+}
+
+pub fn is_connection_lost(error: &anyhow::Error) -> bool {
+    error.is::<ConnectionClosed>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scrape_error(error: ScrapeError) -> anyhow::Error {
+        error.into()
+    }
+
+    #[test]
+    fn browser_errors_map_like_their_variants() {
+        assert_eq!(action_for(&Timeout.into()), ErrorAction::Retry);
+        assert_eq!(action_for(&NoElementFound {}.into()), ErrorAction::SkipGig);
+        assert_eq!(action_for(&ConnectionClosed {}.into()), ErrorAction::Stop);
+        assert_eq!(action_for(&anyhow::anyhow!("unknown")), ErrorAction::Stop);
+
+        let not_found = ScrapeError::not_found(".gig-title", "on the gig page");
+        let error = not_found(Timeout.into());
+        assert_eq!(action_for(&error), ErrorAction::SkipGig);
+        assert_eq!(selector_of(&error), Some(".gig-title"));
+    }
+
+    #[test]
+    fn scrape_errors_map_to_actions() {
+        let actions = [
+            (
+                ScrapeError::AttributeMissing {
+                    selector: "a".to_owned(),
+                    attribute: "href".to_owned(),
+                },
+                ErrorAction::SkipGig,
+            ),
+            (
+                ScrapeError::NavigationTimeout {
+                    page: "gig page".to_owned(),
+                    message: "timed out".to_owned(),
+                },
+                ErrorAction::Retry,
+            ),
+            (
+                ScrapeError::PaginationExhausted { page: 3 },
+                ErrorAction::SwitchTarget,
+            ),
+            (
+                ScrapeError::BlockedByBotProtection {
+                    url: "https://www.fiverr.com".to_owned(),
+                    reloads: 5,
+                },
+                ErrorAction::Stop,
+            ),
+        ];
+        for (error, action) in actions {
+            assert_eq!(action_for(&scrape_error(error)), action);
+        }
+    }
+
+    #[test]
+    fn store_errors_are_not_restartable() {
+        let store_error = scrape_error(ScrapeError::StoreError(sqlx::Error::RowNotFound.into()));
+        assert!(!is_restartable(&store_error));
+        assert!(!is_restartable(&sqlx::Error::PoolClosed.into()));
+        assert!(is_restartable(&ConnectionClosed {}.into()));
+        assert!(is_restartable(&Timeout.into()));
+    }
+
+    #[test]
+    fn gigs_are_blamed_only_for_their_own_failures() {
+        let blocked = scrape_error(ScrapeError::BlockedByBotProtection {
+            url: "https://www.fiverr.com".to_owned(),
+            reloads: 5,
+        });
+        assert!(!is_caused_by_gig(&blocked));
+        assert!(!is_caused_by_gig(&ConnectionClosed {}.into()));
+        assert!(is_connection_lost(&ConnectionClosed {}.into()));
+        assert!(!is_caused_by_gig(&sqlx::Error::PoolClosed.into()));
+        assert!(!is_caused_by_gig(&Timeout.into()));
+        let navigation_timeout = scrape_error(ScrapeError::NavigationTimeout {
+            page: "gig page".to_owned(),
+            message: "timed out".to_owned(),
+        });
+        assert!(!is_caused_by_gig(&navigation_timeout));
+
+        assert!(is_caused_by_gig(&NoElementFound {}.into()));
+        let download_error = scrape_error(ScrapeError::DownloadError {
+            url: "https://fiverr-res.cloudinary.com/video.mp4".to_owned(),
+            message: "404".to_owned(),
+        });
+        assert!(is_caused_by_gig(&download_error));
+    }
+}
@@ -0,0 +1,323 @@
+use std::fmt;
+
+use anyhow::Result;
+use regex::Regex;
+
+use crate::{GigCardData, app_config::GigFilterConfig};
+
+/// Why a gig card did not pass the [`GigFilter`].
+#[derive(Debug)]
+pub enum SkipReason {
+    /// The card shows no review count that could be parsed.
+    ReviewCountUnknown,
+    TooFewReviews {
+        count: u32,
+        min: u32,
+    },
+    TooManyReviews {
+        count: u32,
+        max: u32,
+    },
+    RatingUnknown,
+    RatingTooLow {
+        rating: f64,
+        min: f64,
+    },
+    PriceUnknown,
+    PriceTooLow {
+        price: f64,
+        min: f64,
+    },
+    PriceTooHigh {
+        price: f64,
+        max: f64,
+    },
+    SellerLevelUnknown,
+    SellerLevelNotAllowed(String),
+    TitleUnknown,
+    TitleNotIncluded,
+    TitleExcluded {
+        pattern: String,
+    },
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::ReviewCountUnknown => write!(f, "review count unknown"),
+            SkipReason::TooFewReviews { count, min } => {
+                write!(f, "{count} reviews is fewer than {min}")
+            }
+            SkipReason::TooManyReviews { count, max } => {
+                write!(f, "{count} reviews is more than {max}")
+            }
+            SkipReason::RatingUnknown => write!(f, "rating unknown"),
+            SkipReason::RatingTooLow { rating, min } => {
+                write!(f, "rating {rating} is below {min}")
+            }
+            SkipReason::PriceUnknown => write!(f, "starting price unknown"),
+            SkipReason::PriceTooLow { price, min } => {
+                write!(f, "starting price {price} is below {min}")
+            }
+            SkipReason::PriceTooHigh { price, max } => {
+                write!(f, "starting price {price} is above {max}")
+            }
+            SkipReason::SellerLevelUnknown => write!(f, "seller level unknown"),
+            SkipReason::SellerLevelNotAllowed(level) => {
+                write!(f, "seller level '{level}' is not allowed")
+            }
+            SkipReason::TitleUnknown => write!(f, "title unknown"),
+            SkipReason::TitleNotIncluded => write!(f, "title matches no include pattern"),
+            SkipReason::TitleExcluded { pattern } => {
+                write!(f, "title matches exclude pattern '{pattern}'")
+            }
+        }
+    }
+}
+
+/// Decides which gig cards of a listing get scraped, built from a [`GigFilterConfig`].
+pub struct GigFilter {
+    config: GigFilterConfig,
+    title_include: Vec<Regex>,
+    title_exclude: Vec<Regex>,
+}
+
+impl GigFilter {
+    pub fn new(config: GigFilterConfig) -> Result<Self> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| Regex::new(pattern))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            title_include: compile(&config.title_include)?,
+            title_exclude: compile(&config.title_exclude)?,
+            config,
+        })
+    }
+
+    /// Passes the card when it meets every configured bound. A card lacking a value (`None`) that
+    /// a configured bound checks is skipped rather than passed, e.g. an unparseable review count
+    /// fails `min_reviews` with [`SkipReason::ReviewCountUnknown`].
+    pub fn check(&self, card: &GigCardData) -> Result<(), SkipReason> {
+        let config = &self.config;
+
+        if config.min_reviews.is_some() || config.max_reviews.is_some() {
+            let count = card.review_count.ok_or(SkipReason::ReviewCountUnknown)?;
+            if let Some(min) = config.min_reviews.filter(|min| count < *min) {
+                return Err(SkipReason::TooFewReviews { count, min });
+            }
+            if let Some(max) = config.max_reviews.filter(|max| count > *max) {
+                return Err(SkipReason::TooManyReviews { count, max });
+            }
+        }
+
+        if let Some(min) = config.min_rating {
+            let rating = card.rating.ok_or(SkipReason::RatingUnknown)?;
+            if rating < min {
+                return Err(SkipReason::RatingTooLow { rating, min });
+            }
+        }
+
+        if config.min_price.is_some() || config.max_price.is_some() {
+            let price = card
+                .starting_price
+                .as_ref()
+                .ok_or(SkipReason::PriceUnknown)?
+                .amount;
+            if let Some(min) = config.min_price.filter(|min| price < *min) {
+                return Err(SkipReason::PriceTooLow { price, min });
+            }
+            if let Some(max) = config.max_price.filter(|max| price > *max) {
+                return Err(SkipReason::PriceTooHigh { price, max });
+            }
+        }
+
+        if !config.seller_levels.is_empty() {
+            let level = card
+                .seller_level
+                .as_ref()
+                .ok_or(SkipReason::SellerLevelUnknown)?;
+            let allowed = config
+                .seller_levels
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(level));
+            if !allowed {
+                return Err(SkipReason::SellerLevelNotAllowed(level.to_owned()));
+            }
+        }
+
+        if !(self.title_include.is_empty() && self.title_exclude.is_empty()) {
+            let title = card.title.as_ref().ok_or(SkipReason::TitleUnknown)?;
+            if !self.title_include.is_empty()
+                && !self.title_include.iter().any(|regex| regex.is_match(title))
+            {
+                return Err(SkipReason::TitleNotIncluded);
+            }
+            if let Some(regex) = self
+                .title_exclude
+                .iter()
+                .find(|regex| regex.is_match(title))
+            {
+                return Err(SkipReason::TitleExcluded {
+                    pattern: regex.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Price;
+
+    fn filter(config: GigFilterConfig) -> GigFilter {
+        GigFilter::new(config).unwrap()
+    }
+
+    fn card() -> GigCardData {
+        GigCardData {
+            url: "https://www.fiverr.com/seller/gig".to_owned(),
+            title: Some("I will build your Rust backend".to_owned()),
+            seller_level: Some("Level 2".to_owned()),
+            rating: Some(4.9),
+            review_count: Some(250),
+            starting_price: Some(Price {
+                amount: 50.0,
+                currency: Some("USD".to_owned()),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_filter_requires_100_reviews() {
+        let filter = filter(GigFilterConfig::default());
+        assert!(filter.check(&card()).is_ok());
+
+        let few_reviews = GigCardData {
+            review_count: Some(99),
+            ..card()
+        };
+        assert!(matches!(
+            filter.check(&few_reviews),
+            Err(SkipReason::TooFewReviews {
+                count: 99,
+                min: 100
+            })
+        ));
+    }
+
+    #[test]
+    fn missing_values_are_skipped_only_when_checked() {
+        let unknown_count = GigCardData {
+            review_count: None,
+            ..card()
+        };
+        assert!(matches!(
+            filter(GigFilterConfig::default()).check(&unknown_count),
+            Err(SkipReason::ReviewCountUnknown)
+        ));
+
+        let unchecked = filter(GigFilterConfig {
+            min_reviews: None,
+            ..Default::default()
+        });
+        let unknown_everything = GigCardData {
+            url: card().url,
+            ..Default::default()
+        };
+        assert!(unchecked.check(&unknown_everything).is_ok());
+    }
+
+    #[test]
+    fn bounds_are_inclusive() {
+        let filter = filter(GigFilterConfig {
+            min_reviews: Some(250),
+            max_reviews: Some(250),
+            min_rating: Some(4.9),
+            min_price: Some(50.0),
+            max_price: Some(50.0),
+            ..Default::default()
+        });
+        assert!(filter.check(&card()).is_ok());
+
+        let expensive = GigCardData {
+            starting_price: Some(Price {
+                amount: 51.0,
+                currency: None,
+            }),
+            ..card()
+        };
+        assert!(matches!(
+            filter.check(&expensive),
+            Err(SkipReason::PriceTooHigh { .. })
+        ));
+        let low_rating = GigCardData {
+            rating: Some(4.8),
+            ..card()
+        };
+        assert!(matches!(
+            filter.check(&low_rating),
+            Err(SkipReason::RatingTooLow { .. })
+        ));
+    }
+
+    #[test]
+    fn seller_levels_match_ignoring_case() {
+        let filter = filter(GigFilterConfig {
+            seller_levels: vec!["level 2".to_owned(), "Top Rated".to_owned()],
+            ..Default::default()
+        });
+        assert!(filter.check(&card()).is_ok());
+
+        let new_seller = GigCardData {
+            seller_level: Some("New Seller".to_owned()),
+            ..card()
+        };
+        assert!(matches!(
+            filter.check(&new_seller),
+            Err(SkipReason::SellerLevelNotAllowed(level)) if level == "New Seller"
+        ));
+    }
+
+    #[test]
+    fn title_patterns() {
+        let filter = filter(GigFilterConfig {
+            title_include: vec!["(?i)rust".to_owned()],
+            title_exclude: vec!["(?i)wordpress".to_owned()],
+            ..Default::default()
+        });
+        assert!(filter.check(&card()).is_ok());
+
+        let python = GigCardData {
+            title: Some("I will write Python scripts".to_owned()),
+            ..card()
+        };
+        assert!(matches!(
+            filter.check(&python),
+            Err(SkipReason::TitleNotIncluded)
+        ));
+        let wordpress = GigCardData {
+            title: Some("I will port WordPress plugins to Rust".to_owned()),
+            ..card()
+        };
+        assert!(matches!(
+            filter.check(&wordpress),
+            Err(SkipReason::TitleExcluded { pattern }) if pattern == "(?i)wordpress"
+        ));
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        let config = GigFilterConfig {
+            title_include: vec!["(".to_owned()],
+            ..Default::default()
+        };
+        assert!(GigFilter::new(config).is_err());
+    }
+}
@@ -0,0 +1,323 @@
+use scraper::{ElementRef, Html, Node};
+
+/// Converts the HTML of a gig description to normalized plain text, keeping paragraphs, line
+/// breaks and list items.
+pub fn to_plain_text(html: &str) -> String {
+    convert(html, Flavor::PlainText)
+}
+
+/// Converts the HTML of a gig description to Markdown, keeping paragraphs, line breaks, lists,
+/// headings, emphasis and links.
+pub fn to_markdown(html: &str) -> String {
+    convert(html, Flavor::Markdown)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Flavor {
+    PlainText,
+    Markdown,
+}
+
+fn convert(html: &str, flavor: Flavor) -> String {
+    let fragment = Html::parse_fragment(html);
+    let mut writer = Writer {
+        flavor,
+        out: String::new(),
+        lists: Vec::new(),
+        emphasis: Vec::new(),
+    };
+    writer.write_children(fragment.root_element());
+    normalize(&writer.out, flavor)
+}
+
+struct Writer {
+    flavor: Flavor,
+    out: String,
+    /// The open lists, innermost last, with the next item number of ordered lists.
+    lists: Vec<Option<u32>>,
+    /// The Markdown emphasis markers of the open emphasis elements.
+    emphasis: Vec<&'static str>,
+}
+
+impl Writer {
+    fn write_children(&mut self, element: ElementRef<'_>) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.write_text(text),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.write_element(child);
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn write_element(&mut self, element: ElementRef<'_>) {
+        let markdown = self.flavor == Flavor::Markdown;
+        match element.value().name() {
+            "script" | "style" => (),
+            // A Markdown line break needs a trailing backslash, unless the line is empty anyway.
+            "br" if markdown && !self.at_line_start() => self.out.push_str("\\\n"),
+            "br" => self.out.push('\n'),
+            "p" | "div" | "section" | "blockquote" => {
+                self.block_break();
+                self.write_children(element);
+                self.block_break();
+            }
+            heading @ ("h1" | "h2" | "h3" | "h4" | "h5" | "h6") => {
+                self.block_break();
+                if markdown {
+                    let level = heading[1..].parse().unwrap_or(1);
+                    self.out.push_str(&"#".repeat(level));
+                    self.out.push(' ');
+                }
+                self.write_children(element);
+                self.block_break();
+            }
+            "strong" | "b" if markdown => self.write_wrapped(element, "**"),
+            "em" | "i" if markdown => self.write_wrapped(element, "*"),
+            "a" if markdown => match element.value().attr("href") {
+                Some(href) => {
+                    self.out.push('[');
+                    self.write_children(element);
+                    self.out.push_str("](");
+                    self.out.push_str(
+                        &href
+                            .replace(' ', "%20")
+                            .replace('(', "%28")
+                            .replace(')', "%29"),
+                    );
+                    self.out.push(')');
+                }
+                None => self.write_children(element),
+            },
+            list @ ("ul" | "ol") => {
+                if self.lists.is_empty() {
+                    self.block_break();
+                }
+                self.lists.push((list == "ol").then_some(1));
+                self.write_children(element);
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.block_break();
+                }
+            }
+            "li" => {
+                self.line_break();
+                let depth = self.lists.len().max(1);
+                self.out.push_str(&"  ".repeat(depth - 1));
+                match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        self.out.push_str(&format!("{number}. "));
+                        *number += 1;
+                    }
+                    _ => self.out.push_str("- "),
+                }
+                self.write_children(element);
+            }
+            _ => self.write_children(element),
+        }
+    }
+
+    /// Wraps the content of the element in emphasis markers. Surrounding whitespace goes outside
+    /// the markers since Markdown does not allow it inside, and emphasis already open is not
+    /// opened again.
+    fn write_wrapped(&mut self, element: ElementRef<'_>, marker: &'static str) {
+        if self.emphasis.contains(&marker) {
+            self.write_children(element);
+            return;
+        }
+        let start = self.out.len();
+        self.emphasis.push(marker);
+        self.write_children(element);
+        self.emphasis.pop();
+        let content = self.out.split_off(start);
+        let trimmed = content.trim();
+        if trimmed.is_empty() {
+            self.out.push_str(&content);
+            return;
+        }
+        let leading = &content[..content.len() - content.trim_start().len()];
+        let trailing = &content[content.trim_end().len()..];
+        self.out.push_str(leading);
+        self.out.push_str(marker);
+        self.out.push_str(trimmed);
+        self.out.push_str(marker);
+        self.out.push_str(trailing);
+    }
+
+    /// Writes text with whitespace collapsed the way a browser renders it.
+    fn write_text(&mut self, text: &str) {
+        let mut collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if collapsed.is_empty() {
+            if !text.is_empty() && !self.at_word_boundary() {
+                self.out.push(' ');
+            }
+            return;
+        }
+        if text.starts_with(char::is_whitespace) && !self.at_word_boundary() {
+            collapsed.insert(0, ' ');
+        }
+        if text.ends_with(char::is_whitespace) {
+            collapsed.push(' ');
+        }
+        if self.flavor == Flavor::Markdown {
+            collapsed = escape_markdown(&collapsed, self.at_line_start());
+        }
+        self.out.push_str(&collapsed);
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+
+    fn at_word_boundary(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with([' ', '\n'])
+    }
+
+    fn line_break(&mut self) {
+        if !(self.out.is_empty() || self.out.ends_with('\n')) {
+            self.out.push('\n');
+        }
+    }
+
+    fn block_break(&mut self) {
+        self.line_break();
+        if !(self.out.is_empty() || self.out.ends_with("\n\n")) {
+            self.out.push('\n');
+        }
+    }
+}
+
+/// Escapes the characters of text that Markdown would read as formatting. Block markers only need
+/// escaping at the start of a line.
+fn escape_markdown(text: &str, at_line_start: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    if !at_line_start {
+        return escaped;
+    }
+    let content = escaped.trim_start();
+    let indent = escaped.len() - content.len();
+    if content.starts_with(['#', '-', '+', '>']) {
+        escaped.insert(indent, '\\');
+    } else if let Some(number_end) = content.find(|c: char| !c.is_ascii_digit())
+        && number_end > 0
+        && content[number_end..].starts_with(['.', ')'])
+    {
+        escaped.insert(indent + number_end, '\\');
+    }
+    escaped
+}
+
+/// Trims trailing spaces, drops leading spaces outside list indentation and collapses runs of
+/// blank lines. Markdown line breaks ending a paragraph are dropped.
+fn normalize(text: &str, flavor: Flavor) -> String {
+    let mut lines: Vec<(&str, bool)> = Vec::new();
+    let mut blank_lines = 0;
+    for line in text.lines() {
+        let line = line.trim_end();
+        let trimmed = line.trim_start();
+        let line = match trimmed.starts_with("- ")
+            || trimmed.split_once(". ").is_some_and(|(number, _)| {
+                !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+            }) {
+            true => line,
+            false => trimmed,
+        };
+        if line.is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        lines.push((line, !lines.is_empty() && blank_lines > 0));
+        blank_lines = 0;
+    }
+
+    let mut normalized = String::with_capacity(text.len());
+    for (idx, (line, after_blank)) in lines.iter().enumerate() {
+        let mut line = *line;
+        let ends_paragraph = lines
+            .get(idx + 1)
+            .is_none_or(|(_, after_blank)| *after_blank);
+        let trailing_backslashes = line.len() - line.trim_end_matches('\\').len();
+        if flavor == Flavor::Markdown && ends_paragraph && trailing_backslashes % 2 == 1 {
+            line = line[..line.len() - 1].trim_end();
+        }
+        if idx > 0 {
+            normalized.push_str(if *after_blank { "\n\n" } else { "\n" });
+        }
+        normalized.push_str(line);
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paragraphs_and_line_breaks() {
+        let html = "<p>First line<br>second line</p><p>Next paragraph<br></p>";
+        assert_eq!(
+            to_plain_text(html),
+            "First line\nsecond line\n\nNext paragraph"
+        );
+        assert_eq!(
+            to_markdown(html),
+            "First line\\\nsecond line\n\nNext paragraph"
+        );
+    }
+
+    #[test]
+    fn lists() {
+        let html = "<ul><li>One</li><li>Two<ol><li>Nested</li><li>Again</li></ol></li></ul>";
+        assert_eq!(to_markdown(html), "- One\n- Two\n  1. Nested\n  2. Again");
+    }
+
+    #[test]
+    fn links() {
+        let html = r#"<p>See <a href="https://example.com/a (b)">my portfolio</a>.</p>"#;
+        assert_eq!(
+            to_markdown(html),
+            "See [my portfolio](https://example.com/a%20%28b%29)."
+        );
+        assert_eq!(to_plain_text(html), "See my portfolio.");
+    }
+
+    #[test]
+    fn emphasis_keeps_whitespace_outside_markers() {
+        assert_eq!(
+            to_markdown("<p>I offer<strong> fast </strong>delivery</p>"),
+            "I offer **fast** delivery"
+        );
+        assert_eq!(to_markdown("<p>a<em> </em>b</p>"), "a b");
+    }
+
+    #[test]
+    fn nested_emphasis() {
+        assert_eq!(
+            to_markdown("<b>bold <strong>still bold</strong> <em>and italic</em></b>"),
+            "**bold still bold *and italic***"
+        );
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(
+            to_markdown("<p>2*3 = 6, snake_case, [x] and C:\\</p>"),
+            "2\\*3 = 6, snake\\_case, \\[x\\] and C:\\\\"
+        );
+        assert_eq!(
+            to_markdown("<p># not a heading</p><p>1. not a list</p><p>- nor this</p>"),
+            "\\# not a heading\n\n1\\. not a list\n\n\\- nor this"
+        );
+        assert_eq!(to_plain_text("<p># 2*3</p>"), "# 2*3");
+    }
+}
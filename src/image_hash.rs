@@ -0,0 +1,206 @@
+use anyhow::Result;
+use image::{DynamicImage, GrayImage, imageops::FilterType};
+
+/// Perceptual hashes of an image. Images that look alike have hashes a small Hamming distance
+/// apart, even after resizing, light cropping or recolouring.
+#[derive(Debug, Clone, Copy)]
+pub struct ImageHashes {
+    /// Which pixels of an 8x8 thumbnail are brighter than its mean.
+    pub ahash: u64,
+    /// Which pixels of a 9x8 thumbnail are brighter than their right neighbour.
+    pub dhash: u64,
+    /// Which low frequencies of a 32x32 thumbnail's DCT are above their median.
+    pub phash: u64,
+}
+
+impl ImageHashes {
+    pub fn compute(bytes: &[u8]) -> Result<Self> {
+        let image = image::load_from_memory(bytes)?;
+        Ok(Self {
+            ahash: ahash(&image),
+            dhash: dhash(&image),
+            phash: phash(&image),
+        })
+    }
+}
+
+/// Hashes are stored as 16 hex digits.
+pub fn to_hex(hash: u64) -> String {
+    format!("{hash:016x}")
+}
+
+pub fn from_hex(hex: &str) -> Option<u64> {
+    u64::from_str_radix(hex, 16).ok()
+}
+
+pub fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Groups the hashes into clusters in which every two hashes are at most `max_distance` apart
+/// (complete linkage), returning the indexes of the hashes in clusters of two or more. Each hash
+/// joins the cluster whose farthest member is closest to it, in the order of the hashes, so a
+/// chain of hashes each close to the next does not end up in one cluster.
+pub fn clusters(hashes: &[u64], max_distance: u32) -> Vec<Vec<usize>> {
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    for (i, &hash) in hashes.iter().enumerate() {
+        let closest = clusters
+            .iter()
+            .enumerate()
+            .filter_map(|(cluster, members)| {
+                let farthest = members
+                    .iter()
+                    .map(|&member| distance(hash, hashes[member]))
+                    .max()?;
+                (farthest <= max_distance).then_some((farthest, cluster))
+            })
+            .min();
+        match closest {
+            Some((_, cluster)) => clusters[cluster].push(i),
+            None => clusters.push(vec![i]),
+        }
+    }
+    clusters.retain(|cluster| cluster.len() > 1);
+    clusters
+}
+
+fn thumbnail(image: &DynamicImage, width: u32, height: u32) -> GrayImage {
+    image
+        .resize_exact(width, height, FilterType::Triangle)
+        .to_luma8()
+}
+
+fn bits(values: impl Iterator<Item = bool>) -> u64 {
+    values.fold(0, |hash, bit| (hash << 1) | bit as u64)
+}
+
+fn ahash(image: &DynamicImage) -> u64 {
+    let thumbnail = thumbnail(image, 8, 8);
+    let mean = thumbnail.pixels().map(|p| p.0[0] as u32).sum::<u32>() / 64;
+    bits(thumbnail.pixels().map(|p| p.0[0] as u32 > mean))
+}
+
+fn dhash(image: &DynamicImage) -> u64 {
+    let thumbnail = thumbnail(image, 9, 8);
+    bits((0..8).flat_map(|y| {
+        let thumbnail = &thumbnail;
+        (0..8).map(move |x| thumbnail.get_pixel(x, y).0[0] > thumbnail.get_pixel(x + 1, y).0[0])
+    }))
+}
+
+fn phash(image: &DynamicImage) -> u64 {
+    const SIZE: usize = 32;
+    let thumbnail = thumbnail(image, SIZE as u32, SIZE as u32);
+    let pixels: Vec<f64> = thumbnail.pixels().map(|p| p.0[0] as f64).collect();
+
+    // A separable 2D DCT-II, of which only the 8x8 lowest frequencies are kept.
+    let cosines: Vec<f64> = (0..8 * SIZE)
+        .map(|i| {
+            let (u, x) = (i / SIZE, i % SIZE);
+            (std::f64::consts::PI * u as f64 * (2 * x + 1) as f64 / (2 * SIZE) as f64).cos()
+        })
+        .collect();
+    let mut rows = vec![0.0; SIZE * 8];
+    for y in 0..SIZE {
+        for u in 0..8 {
+            rows[y * 8 + u] = (0..SIZE)
+                .map(|x| pixels[y * SIZE + x] * cosines[u * SIZE + x])
+                .sum();
+        }
+    }
+    let mut frequencies = Vec::with_capacity(64);
+    for v in 0..8 {
+        for u in 0..8 {
+            frequencies.push(
+                (0..SIZE)
+                    .map(|y| rows[y * 8 + u] * cosines[v * SIZE + y])
+                    .sum::<f64>(),
+            );
+        }
+    }
+
+    // The DC term only reflects the overall brightness, so it is left out of the median.
+    let mut sorted = frequencies[1..].to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let median = sorted[sorted.len() / 2];
+    bits(frequencies.iter().map(|&frequency| frequency > median))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{ImageFormat, RgbImage};
+
+    use super::*;
+
+    /// A PNG of a soft bright blob on a gradient, optionally with the brightness inverted.
+    fn blob(size: u32, inverted: bool) -> Vec<u8> {
+        let image = RgbImage::from_fn(size, size, |x, y| {
+            let (x, y) = (x as f64 / size as f64, y as f64 / size as f64);
+            let blob = (-((x - 0.3).powi(2) + (y - 0.6).powi(2)) * 12.0).exp();
+            let value = (60.0 * x + 40.0 * y + 150.0 * blob).min(255.0) as u8;
+            let value = if inverted { 255 - value } else { value };
+            image::Rgb([value, value / 2, 255 - value / 2])
+        });
+        let mut png = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(image)
+            .write_to(&mut png, ImageFormat::Png)
+            .unwrap();
+        png.into_inner()
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        assert_eq!(to_hex(0xab), "00000000000000ab");
+        assert_eq!(from_hex(&to_hex(u64::MAX)), Some(u64::MAX));
+        assert_eq!(from_hex("not hex"), None);
+    }
+
+    #[test]
+    fn distance_counts_differing_bits() {
+        assert_eq!(distance(0, 0), 0);
+        assert_eq!(distance(0b1011, 0b0010), 2);
+        assert_eq!(distance(0, u64::MAX), 64);
+    }
+
+    #[test]
+    fn resized_images_hash_alike() -> Result<()> {
+        let original = ImageHashes::compute(&blob(256, false))?;
+        let resized = ImageHashes::compute(&blob(96, false))?;
+        let inverted = ImageHashes::compute(&blob(256, true))?;
+        assert!(distance(original.phash, resized.phash) <= 6);
+        assert!(distance(original.dhash, resized.dhash) <= 6);
+        assert!(distance(original.phash, inverted.phash) > 20);
+        Ok(())
+    }
+
+    #[test]
+    fn clusters_drop_singletons() {
+        let hashes = [0b0000, 0xffff_0000, 0b0001, 0xffff_0000];
+        assert_eq!(clusters(&hashes, 1), vec![vec![0, 2], vec![1, 3]]);
+        assert!(clusters(&[0, u64::MAX], 6).is_empty());
+    }
+
+    #[test]
+    fn clusters_do_not_chain() {
+        // Each hash is 2 bits from the next, but the ends are 4 bits apart.
+        let hashes = [0b0000, 0b0011, 0b1111];
+        let clusters = clusters(&hashes, 2);
+        assert_eq!(clusters, vec![vec![0, 1]]);
+    }
+
+    #[test]
+    fn hashes_join_the_closest_cluster() {
+        // The last hash is at most 3 bits from every member of both clusters, but at most 1
+        // bit from those of the second one.
+        let hashes = [
+            0b0000_0000,
+            0b0000_0001,
+            0b0000_1111,
+            0b0001_0111,
+            0b0000_0111,
+        ];
+        assert_eq!(clusters(&hashes, 3), vec![vec![0, 1], vec![2, 3, 4]]);
+    }
+}
@@ -1,30 +1,58 @@
 mod app_config;
+mod cli;
+mod error;
+mod gig_filter;
+mod html_text;
+mod image_hash;
+mod media_store;
+mod media_type;
+mod migrate;
+mod video_processing;
 
 use std::{
     cmp::Ordering,
-    num::ParseIntError,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
     str::FromStr,
-    sync::Arc,
+    sync::{Arc, Mutex},
     time::Duration,
 };
 
 use anyhow::{Result, anyhow};
-use app_config::AppConfig;
+use app_config::{AppConfig, MediaCapture, ScrapeTarget, SupervisorConfig, TargetRotation};
+use clap::Parser;
+use cli::{Cli, Command, GigsCommand, MediaCommand, MigrateCommand, VisualsCommand};
+use error::{ErrorAction, ScrapeError};
 use figment::{
     Figment,
     providers::{Format, Yaml},
 };
 use flexi_logger::Logger;
-use headless_chrome::{Browser, Element, Tab, protocol::cdp::Runtime::RemoteObject};
-use sqlx::{QueryBuilder, Sqlite, SqlitePool, sqlite::SqliteConnectOptions};
+use futures::{StreamExt, stream};
+use gig_filter::{GigFilter, SkipReason};
+use headless_chrome::{
+    Browser, Element, Tab,
+    protocol::cdp::{Network, Runtime::RemoteObject},
+};
+use image_hash::ImageHashes;
+use media_store::MediaStore;
+use sha2::{Digest, Sha256};
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, SqlitePool, sqlite::SqliteConnectOptions};
 use tokio::{fs, io::AsyncWriteExt, time::sleep};
 use url::Url;
 use uuid::Uuid;
+use video_processing::{VideoMetadata, VideoProcessor};
 
 static BTN_CLICK_WAIT_SECS: u64 = 1;
 static PAGE_RELOAD_WAIT_SECS: u64 = 5;
+static MAX_GALLERY_SLIDES: usize = 50;
+static MAX_ERROR_PAGE_RELOADS: u32 = 5;
+static MAX_STEP_RETRIES: u32 = 3;
 static BASE_URL: &str = "https://www.fiverr.com";
+static MEDIA_CDN_HOST: &str = "fiverr-res.cloudinary.com";
+static MEDIA_CAPTURE_HANDLER: &str = "gig-media";
+static DOWNLOAD_PROGRESS_STEP_BYTES: u64 = 10 * 1024 * 1024;
+static MAX_DOWNLOAD_RETRY_DELAY_SECS: u64 = 300;
 
 struct CustomBrowser {
     browser: Browser,
@@ -63,6 +91,118 @@ impl CustomBrowser {
         tab.close(false)?;
         Ok(())
     }
+
+    /// Enables the DevTools Network domain on the tab and records the gig media responses served
+    /// by Fiverr's media CDN until [`Self::stop_capturing_media`] is called.
+    fn capture_media(&self, tab: &Arc<Tab>) -> Result<CapturedMediaLog> {
+        let captured_media = CapturedMediaLog::default();
+        let log = captured_media.clone();
+        tab.register_response_handling(
+            MEDIA_CAPTURE_HANDLER,
+            Box::new(move |params, fetch_body| {
+                if let Some(media) = CapturedMedia::from_response(&params.response, fetch_body) {
+                    log::debug!("Captured media: {}", media.url);
+                    log.lock().unwrap().push(media);
+                }
+            }),
+        )?;
+        Ok(captured_media)
+    }
+
+    /// Stops recording gig media and disables the Network domain again so the tab no longer
+    /// buffers responses.
+    fn stop_capturing_media(&self, tab: &Arc<Tab>) -> Result<()> {
+        tab.deregister_response_handling(MEDIA_CAPTURE_HANDLER)?;
+        tab.call_method(Network::Disable(None))?;
+        Ok(())
+    }
+}
+
+/// A gig media response recorded from the DevTools Network domain.
+#[derive(Debug, Clone)]
+struct CapturedMedia {
+    url: String,
+    mime_type: String,
+    /// The size of the whole file, if the response tells it.
+    byte_size: Option<u64>,
+}
+
+type CapturedMediaLog = Arc<Mutex<Vec<CapturedMedia>>>;
+
+impl CapturedMedia {
+    /// Records the response if it serves gig media. The file size comes from the headers, or for
+    /// chunked responses without a length from the body itself; video bodies are not fetched
+    /// since they can be large.
+    fn from_response(
+        response: &Network::Response,
+        fetch_body: &dyn Fn() -> Result<Network::GetResponseBodyReturnObject>,
+    ) -> Option<Self> {
+        let url = Url::parse(&response.url).ok()?;
+        if url.host_str() != Some(MEDIA_CDN_HOST) {
+            return None;
+        }
+        let mime_type = response.mime_type.to_lowercase();
+        let is_gig_media = match mime_type.split('/').next() {
+            // Cards of other gigs shown on the page are served from the same folder.
+            Some("image") => url.path().contains("/gigs/") && !url.path().contains("gig_card"),
+            Some("video") => true,
+            _ => mime_type == "application/pdf",
+        };
+        if !is_gig_media {
+            return None;
+        }
+        let header = |name: &str| {
+            response
+                .headers
+                .0
+                .as_ref()?
+                .as_object()?
+                .iter()
+                .find(|(header, _)| header.eq_ignore_ascii_case(name))?
+                .1
+                .as_str()
+                .map(str::to_owned)
+        };
+        // The length of a range response is that of the range; the total follows the slash.
+        let byte_size = header("content-range")
+            .and_then(|range| range.rsplit('/').next()?.trim().parse().ok())
+            .or_else(|| header("content-length")?.trim().parse().ok())
+            .or_else(|| {
+                if mime_type.starts_with("video/") {
+                    return None;
+                }
+                let body = fetch_body().ok()?;
+                let size = match body.base_64_encoded {
+                    true => body.body.trim_end_matches('=').len() * 3 / 4,
+                    false => body.body.len(),
+                };
+                Some(size as u64)
+            });
+        Some(Self {
+            url: response.url.clone(),
+            mime_type,
+            byte_size,
+        })
+    }
+
+    /// The file name of the asset, which stays the same across the renditions the CDN serves.
+    fn asset_name(&self) -> &str {
+        Self::asset_name_of(&self.url)
+    }
+
+    fn asset_name_of(url: &str) -> &str {
+        let path = url.split(['?', '#']).next().unwrap_or_default();
+        let file_name = path.rsplit('/').next().unwrap_or_default();
+        file_name.split('.').next().unwrap_or_default()
+    }
+
+    fn slide_type(&self) -> SlideType {
+        match self.mime_type.split('/').next() {
+            Some("image") => SlideType::Image,
+            Some("video") => SlideType::Video,
+            _ => SlideType::Pdf,
+        }
+    }
 }
 
 struct FiverrNav<'a> {
@@ -106,29 +246,36 @@ impl<'a> FiverrNav<'a> {
             log::info!("Find element: {elements_selector} {idx} a");
             let anchor = element.find_element("a")?;
             log::info!("Get attribute: {elements_selector} {idx} a.href");
-            let href = anchor.get_attribute_value("href")?.ok_or(anyhow!(format!(
-                "Element ({}) does not have a 'href' attribute",
-                Self::category_element_selector(idx)
-            )))?;
+            let href =
+                anchor
+                    .get_attribute_value("href")?
+                    .ok_or(ScrapeError::AttributeMissing {
+                        selector: Self::category_element_selector(idx),
+                        attribute: "href".to_owned(),
+                    })?;
             if href.contains(category_id) {
                 category_element = Some(element);
                 break;
             }
         }
 
-        category_element.ok_or(anyhow!(format!(
-            "Could not find category element '{}'",
-            category_id
-        )))
+        Ok(category_element.ok_or(ScrapeError::SelectorNotFound {
+            selector: elements_selector.to_owned(),
+            context: format!("for category '{category_id}'"),
+        })?)
     }
 
     async fn scroll_category_el_into_view(&'a self, category_id: &str) -> Result<Element<'a>> {
         loop {
             let category_el = self.get_category_el(category_id)?;
             log::info!("Get attribute: [{category_id}].style");
-            let style = category_el.get_attribute_value("style")?.ok_or(anyhow!(
-                "Category element ({category_id}) does not have a 'style' attribute"
-            ))?;
+            let style =
+                category_el
+                    .get_attribute_value("style")?
+                    .ok_or(ScrapeError::AttributeMissing {
+                        selector: format!("[{category_id}]"),
+                        attribute: "style".to_owned(),
+                    })?;
             if style.contains("none") {
                 self.scroll_right().await?;
             } else {
@@ -149,9 +296,13 @@ impl<'a> FiverrNav<'a> {
                 log::info!("Find element: [el:li] {menu_item_idx} a");
                 let anchor = element.find_element("a")?;
                 log::info!("Get attribute: [el:li] {menu_item_idx} a");
-                let href = anchor.get_attribute_value("href")?.ok_or(anyhow!(format!(
-                    "Element ([el:li] {menu_item_idx} a) does not have a 'href' attribute",
-                )))?;
+                let href =
+                    anchor
+                        .get_attribute_value("href")?
+                        .ok_or(ScrapeError::AttributeMissing {
+                            selector: format!("[el:li] {menu_item_idx} a"),
+                            attribute: "href".to_owned(),
+                        })?;
                 log::debug!("{href}");
                 if href.contains(menu_id) {
                     menu_item_element = Some(element);
@@ -163,10 +314,10 @@ impl<'a> FiverrNav<'a> {
             }
         }
 
-        menu_item_element.ok_or(anyhow!(format!(
-            "Could not find category element '{}'",
-            menu_id
-        )))
+        Ok(menu_item_element.ok_or(ScrapeError::SelectorNotFound {
+            selector: format!("{elements_selector} li"),
+            context: format!("for menu '{menu_id}'"),
+        })?)
     }
 
     async fn go_to(&self, category_id: &str, menu_id: &str) -> Result<()> {
@@ -177,9 +328,72 @@ impl<'a> FiverrNav<'a> {
         category_el.wait_for_element(".menu-bucket")?;
         let menu_item_el = Self::get_menu_el(category_el, menu_id)?;
         menu_item_el.click()?;
-        self.tab.wait_until_navigated()?;
+        NavigationWaiter::wait(self.tab, &format!("{category_id}/{menu_id}"))?;
+        Ok(())
+    }
+
+    async fn search(&self, query: &str) -> Result<()> {
+        let mut search_url = Url::parse(BASE_URL)?.join("/search/gigs")?;
+        search_url.query_pairs_mut().append_pair("query", query);
+        log::info!("Navigate to: {search_url}");
+        self.tab.navigate_to(search_url.as_str())?;
+        NavigationWaiter::wait(self.tab, search_url.as_str())?;
         Ok(())
     }
+
+    async fn go_to_target(&self, target: &ScrapeTarget) -> Result<()> {
+        match &target.search_query {
+            Some(query) => self.search(query).await,
+            None => self.go_to(&target.category, &target.menu).await,
+        }
+    }
+}
+
+/// How far the listing of a scrape target has been worked through.
+struct ScrapeProgress {
+    last_page: u32,
+    last_gig_index: Option<u32>,
+    page_finished: bool,
+}
+
+impl Default for ScrapeProgress {
+    fn default() -> Self {
+        Self {
+            last_page: 1,
+            last_gig_index: None,
+            page_finished: false,
+        }
+    }
+}
+
+/// The metrics shown on a gig's card in a target's listing.
+#[derive(Debug, Default)]
+struct GigCardData {
+    url: String,
+    page: u32,
+    position: u32,
+    title: Option<String>,
+    seller_level: Option<String>,
+    rating: Option<f64>,
+    review_count: Option<u32>,
+    starting_price: Option<Price>,
+    badges: Vec<String>,
+}
+
+/// A gig to scrape again by its URL instead of from a listing.
+struct GigRevisit {
+    target: ScrapeTarget,
+    card: GigCardData,
+}
+
+/// A gig that failed to scrape.
+struct FailedGig {
+    url: String,
+    attempts: i64,
+    last_error: String,
+    selector: Option<String>,
+    last_failed_at: String,
+    quarantined: bool,
 }
 
 struct ScrapedGigsStore {
@@ -191,71 +405,829 @@ impl ScrapedGigsStore {
         Self { db }
     }
 
+    /// Whether the gig needs no visit: it has been scraped completely or is quarantined.
     async fn is_scraped(&self, gig_url: &str) -> Result<bool> {
-        let record = sqlx::query!("SELECT count(*) as cnt FROM gigs WHERE url = $1", gig_url)
-            .fetch_one(&self.db)
+        let record = sqlx::query!(
+            "SELECT
+                EXISTS(SELECT 1 FROM gigs WHERE url = $1 AND status = 'complete')
+                OR EXISTS(SELECT 1 FROM failed_gigs WHERE url = $1 AND quarantined_at IS NOT NULL)
+                AS scraped",
+            gig_url
+        )
+        .fetch_one(&self.db)
+        .await?;
+        Ok(record.scraped != 0)
+    }
+
+    /// Counts a failed attempt of scraping the gig found on the page of the target, and
+    /// quarantines it once it has failed `quarantine_after` times. Returns whether the gig is
+    /// quarantined.
+    async fn record_failed_gig(
+        &self,
+        target: &ScrapeTarget,
+        gig_url: &str,
+        page: u32,
+        error: &anyhow::Error,
+        quarantine_after: u32,
+    ) -> Result<bool> {
+        let last_error = format!("{error:#}");
+        let selector = error::selector_of(error);
+        let search_query = target.search_query_key();
+        let record = sqlx::query!(
+            r#"INSERT INTO failed_gigs(url, attempts, last_error, selector, category, menu, search_query, page)
+            VALUES($1, 1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT(url) DO UPDATE SET
+                attempts = attempts + 1,
+                last_error = excluded.last_error,
+                selector = excluded.selector,
+                last_failed_at = CURRENT_TIMESTAMP
+            RETURNING attempts AS "attempts!: i64""#,
+            gig_url,
+            last_error,
+            selector,
+            target.category,
+            target.menu,
+            search_query,
+            page
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        if record.attempts < i64::from(quarantine_after) {
+            return Ok(false);
+        }
+        sqlx::query!(
+            "UPDATE failed_gigs SET quarantined_at = COALESCE(quarantined_at, CURRENT_TIMESTAMP)
+            WHERE url = $1",
+            gig_url
+        )
+        .execute(&self.db)
+        .await?;
+        Ok(true)
+    }
+
+    async fn failed_gigs(&self) -> Result<Vec<FailedGig>> {
+        let failed_gigs = sqlx::query_as!(
+            FailedGig,
+            r#"SELECT url, attempts, last_error, selector,
+                last_failed_at AS "last_failed_at: String",
+                quarantined_at IS NOT NULL AS "quarantined!: bool"
+            FROM failed_gigs ORDER BY quarantined_at IS NULL, last_failed_at DESC"#
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(failed_gigs)
+    }
+
+    /// Releases the gig, or all gigs when `gig_url` is `None`, from quarantine and resets its
+    /// failure count, so that the revisit pass scrapes it again. Returns how many gigs were
+    /// requeued.
+    async fn requeue_failed_gigs(&self, gig_url: Option<&str>) -> Result<u64> {
+        let result = sqlx::query!(
+            "UPDATE failed_gigs SET attempts = 0, quarantined_at = NULL
+            WHERE $1 IS NULL OR url = $1",
+            gig_url
+        )
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Marks a saved gig as incomplete so that it gets scraped again, forgetting its earlier
+    /// failures. Returns `false` when no gig with the URL exists.
+    async fn mark_incomplete(&self, gig_url: &str) -> Result<bool> {
+        let mut tx = self.db.begin().await?;
+        let result = sqlx::query!(
+            "UPDATE gigs SET status = 'incomplete' WHERE url = $1",
+            gig_url
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!("DELETE FROM failed_gigs WHERE url = $1", gig_url)
+            .execute(&mut *tx)
             .await?;
-        Ok(record.cnt > 0)
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
     }
 
-    async fn save_visuals(&self, gig_id: String, visuals: Vec<VisualData>) -> Result<()> {
-        log::debug!("{:#?}", visuals);
-        let mut query_builder: QueryBuilder<Sqlite> =
-            QueryBuilder::new("INSERT INTO visuals(id, gig_id, file_path, visual_type)");
+    /// The next gig to scrape again by its URL: a gig saved as incomplete, or one that failed to
+    /// scrape and was never saved, that is not quarantined. Incomplete gigs come first. Listings
+    /// never lead back to these once their pages are finished.
+    async fn next_revisit(&self) -> Result<Option<GigRevisit>> {
+        let record = sqlx::query!(
+            r#"SELECT url AS "url!: String", page AS "page!: i64", category AS "category!: String",
+                menu AS "menu!: String", search_query AS "search_query!: String"
+            FROM (
+                SELECT url, page, COALESCE(category, '') AS category, COALESCE(menu, '') AS menu,
+                    search_query, 0 AS failed, rowid AS seq
+                FROM gigs WHERE status = 'incomplete'
+                UNION ALL
+                SELECT url, page, category, menu, search_query, 1, rowid
+                FROM failed_gigs WHERE url NOT IN (SELECT url FROM gigs)
+            )
+            WHERE url NOT IN (SELECT url FROM failed_gigs WHERE quarantined_at IS NOT NULL)
+            ORDER BY failed, seq
+            LIMIT 1"#
+        )
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(record.map(|record| GigRevisit {
+            target: ScrapeTarget {
+                category: record.category,
+                menu: record.menu,
+                search_query: Some(record.search_query).filter(|query| !query.is_empty()),
+            },
+            card: GigCardData {
+                url: record.url,
+                page: record.page as u32,
+                ..Default::default()
+            },
+        }))
+    }
+
+    /// The storage URIs of all downloaded files and poster frames that visuals refer to.
+    async fn referenced_media_files(&self) -> Result<HashSet<String>> {
+        let rows = sqlx::query!(
+            r#"SELECT storage_uri AS "storage_uri!" FROM visuals WHERE storage_uri IS NOT NULL
+            UNION SELECT poster_uri FROM visuals WHERE poster_uri IS NOT NULL"#
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows.into_iter().map(|row| row.storage_uri).collect())
+    }
+
+    /// Downloaded images without perceptual hashes, as visual IDs with storage URIs.
+    async fn unhashed_images(&self) -> Result<Vec<(String, String)>> {
+        let rows = sqlx::query!(
+            r#"SELECT id AS "id!", storage_uri AS "storage_uri!" FROM visuals
+            WHERE storage_uri IS NOT NULL AND mime_type LIKE 'image/%' AND phash IS NULL"#
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.id, row.storage_uri))
+            .collect())
+    }
+
+    async fn save_image_hashes(&self, visual_id: &str, hashes: ImageHashes) -> Result<()> {
+        let [ahash, dhash, phash] =
+            [hashes.ahash, hashes.dhash, hashes.phash].map(image_hash::to_hex);
+        sqlx::query!(
+            "UPDATE visuals SET ahash = $1, dhash = $2, phash = $3 WHERE id = $4",
+            ahash,
+            dhash,
+            phash,
+            visual_id
+        )
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
 
-        let visuals = visuals
+    async fn hashed_images(&self) -> Result<Vec<HashedImage>> {
+        let rows = sqlx::query!(
+            r#"SELECT v.id AS "id!", v.gig_id, g.url AS gig_url, v.storage_uri,
+                v.ahash AS "ahash!", v.dhash AS "dhash!", v.phash AS "phash!"
+            FROM visuals v JOIN gigs g ON g.id = v.gig_id
+            WHERE v.ahash IS NOT NULL AND v.dhash IS NOT NULL AND v.phash IS NOT NULL"#
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows
             .into_iter()
-            .map(|visual| {
-                let id = Uuid::new_v4().to_string();
-                (id, gig_id.to_owned(), visual.url, visual.typ.to_string())
+            .filter_map(|row| {
+                let hashes = ImageHashes {
+                    ahash: image_hash::from_hex(&row.ahash)?,
+                    dhash: image_hash::from_hex(&row.dhash)?,
+                    phash: image_hash::from_hex(&row.phash)?,
+                };
+                Some(HashedImage {
+                    id: row.id,
+                    gig_id: row.gig_id,
+                    gig_url: row.gig_url,
+                    storage_uri: row.storage_uri,
+                    hashes,
+                })
             })
-            .collect::<Vec<_>>();
+            .collect())
+    }
 
-        query_builder.push_values(visuals, |mut b, (id, gig_id, file_path, visual_type)| {
-            b.push_bind(id)
-                .push_bind(gig_id)
-                .push_bind(file_path)
-                .push_bind(visual_type);
+    /// The visuals whose download failed, with the URL to download them from.
+    async fn failed_visuals(&self) -> Result<Vec<FailedVisual>> {
+        let visuals = sqlx::query_as!(
+            FailedVisual,
+            r#"SELECT id AS "id!", source_url AS "source_url!" FROM visuals
+            WHERE storage_uri IS NULL AND error IS NOT NULL AND source_url IS NOT NULL"#
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(visuals)
+    }
+
+    /// Records the outcome of downloading a visual again.
+    async fn update_visual_download(
+        &self,
+        visual_id: &str,
+        outcome: Result<DownloadedFile, DownloadFailure>,
+    ) -> Result<()> {
+        match outcome {
+            Ok(file) => {
+                let byte_size = file.byte_size as i64;
+                let (metadata, poster_uri) = match file.video {
+                    Some(video) => (video.metadata, video.poster_uri),
+                    None => Default::default(),
+                };
+                let [ahash, dhash, phash] = match file.image_hashes {
+                    Some(hashes) => [hashes.ahash, hashes.dhash, hashes.phash]
+                        .map(|hash| Some(image_hash::to_hex(hash))),
+                    None => Default::default(),
+                };
+                sqlx::query!(
+                    "UPDATE visuals SET storage_uri = $1, mime_type = $2, byte_size = $3, sha256 = $4,
+                    downloaded_at = CURRENT_TIMESTAMP, error = NULL, http_status = NULL,
+                    duration_secs = $5, width = $6, height = $7, video_codec = $8, poster_uri = $9,
+                    ahash = $10, dhash = $11, phash = $12
+                    WHERE id = $13",
+                    file.uri,
+                    file.mime_type,
+                    byte_size,
+                    file.sha256,
+                    metadata.duration_secs,
+                    metadata.width,
+                    metadata.height,
+                    metadata.codec,
+                    poster_uri,
+                    ahash,
+                    dhash,
+                    phash,
+                    visual_id
+                )
+                .execute(&self.db)
+                .await?;
+            }
+            Err(failure) => {
+                let http_status = failure.http_status.map(i64::from);
+                sqlx::query!(
+                    "UPDATE visuals SET error = $1, http_status = $2 WHERE id = $3",
+                    failure.error,
+                    http_status,
+                    visual_id
+                )
+                .execute(&self.db)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the plain text and Markdown descriptions of gigs saved without them and returns
+    /// how many gigs were updated.
+    async fn normalize_descriptions(&self) -> Result<u64> {
+        let gigs = sqlx::query!(
+            "SELECT id, description FROM gigs
+            WHERE description_text IS NULL OR description_markdown IS NULL"
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut updated = 0;
+        for gig in gigs {
+            let description_text = html_text::to_plain_text(&gig.description);
+            let description_markdown = html_text::to_markdown(&gig.description);
+            sqlx::query!(
+                "UPDATE gigs SET description_text = $1, description_markdown = $2 WHERE id = $3",
+                description_text,
+                description_markdown,
+                gig.id
+            )
+            .execute(&self.db)
+            .await?;
+            updated += 1;
+        }
+        Ok(updated)
+    }
+
+    async fn save_visuals(
+        conn: &mut SqliteConnection,
+        gig_id: &str,
+        visuals: Vec<DownloadedVisual>,
+    ) -> Result<()> {
+        log::debug!("{:#?}", visuals);
+        sqlx::query!("DELETE FROM visuals WHERE gig_id = $1", gig_id)
+            .execute(&mut *conn)
+            .await?;
+        if visuals.is_empty() {
+            log::warn!("No visuals to save for gig {gig_id}");
+            return Ok(());
+        }
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT INTO visuals(id, gig_id, visual_type, position, source_url, storage_uri, mime_type, byte_size, sha256, downloaded_at, error, http_status, duration_secs, width, height, video_codec, poster_uri, ahash, dhash, phash)",
+        );
+
+        query_builder.push_values(visuals, |mut b, downloaded| {
+            let DownloadedVisual { visual, outcome } = downloaded;
+            b.push_bind(Uuid::new_v4().to_string())
+                .push_bind(gig_id.to_owned())
+                .push_bind(visual.typ.to_string())
+                .push_bind(visual.position)
+                .push_bind(visual.url);
+            match outcome {
+                Ok(file) => {
+                    let (metadata, poster_uri) = match file.video {
+                        Some(video) => (video.metadata, video.poster_uri),
+                        None => Default::default(),
+                    };
+                    b.push_bind(file.uri)
+                        .push_bind(file.mime_type)
+                        .push_bind(file.byte_size as i64)
+                        .push_bind(file.sha256)
+                        .push("CURRENT_TIMESTAMP")
+                        .push("NULL")
+                        .push("NULL")
+                        .push_bind(metadata.duration_secs)
+                        .push_bind(metadata.width)
+                        .push_bind(metadata.height)
+                        .push_bind(metadata.codec)
+                        .push_bind(poster_uri)
+                        .push_bind(
+                            file.image_hashes
+                                .map(|hashes| image_hash::to_hex(hashes.ahash)),
+                        )
+                        .push_bind(
+                            file.image_hashes
+                                .map(|hashes| image_hash::to_hex(hashes.dhash)),
+                        )
+                        .push_bind(
+                            file.image_hashes
+                                .map(|hashes| image_hash::to_hex(hashes.phash)),
+                        );
+                }
+                Err(failure) => {
+                    b.push("NULL")
+                        .push("NULL")
+                        .push("NULL")
+                        .push("NULL")
+                        .push("NULL")
+                        .push_bind(failure.error)
+                        .push_bind(failure.http_status.map(i64::from))
+                        .push("NULL")
+                        .push("NULL")
+                        .push("NULL")
+                        .push("NULL")
+                        .push("NULL")
+                        .push("NULL")
+                        .push("NULL")
+                        .push("NULL");
+                }
+            }
         });
 
         let query = query_builder.build();
-        query.execute(&self.db).await?;
+        query.execute(conn).await?;
+
+        Ok(())
+    }
+
+    async fn save_packages(
+        conn: &mut SqliteConnection,
+        gig_id: &str,
+        packages: Vec<PackageData>,
+    ) -> Result<()> {
+        log::debug!("{:#?}", packages);
+        sqlx::query!("DELETE FROM gig_packages WHERE gig_id = $1", gig_id)
+            .execute(&mut *conn)
+            .await?;
+        for (position, package) in packages.into_iter().enumerate() {
+            let package_id = Uuid::new_v4().to_string();
+            let position = position as i64;
+            let (price, currency) = match package.price {
+                Some(price) => (Some(price.amount), price.currency),
+                None => (None, None),
+            };
+            let (revisions, unlimited_revisions) = match package.revisions {
+                Some(PackageRevisions::Limited(revisions)) => (Some(revisions), false),
+                Some(PackageRevisions::Unlimited) => (None, true),
+                None => (None, false),
+            };
+            sqlx::query!(
+                "INSERT INTO gig_packages(id, gig_id, position, tier, title, description, price, currency, delivery_days, revisions, unlimited_revisions)
+                VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+                package_id,
+                gig_id,
+                position,
+                package.tier,
+                package.title,
+                package.description,
+                price,
+                currency,
+                package.delivery_days,
+                revisions,
+                unlimited_revisions
+            )
+            .execute(&mut *conn)
+            .await?;
 
+            for (position, feature) in package.features.into_iter().enumerate() {
+                let position = position as i64;
+                sqlx::query!(
+                    "INSERT INTO gig_package_features(package_id, position, feature, included)
+                    VALUES($1, $2, $3, $4)",
+                    package_id,
+                    position,
+                    feature.name,
+                    feature.included
+                )
+                .execute(&mut *conn)
+                .await?;
+            }
+        }
         Ok(())
     }
 
-    async fn save(&self, gig: GigData) -> Result<()> {
+    /// Records the listing card of a gig as seen now, whether or not the gig gets scraped.
+    async fn record_listing(&self, target: &ScrapeTarget, card: &GigCardData) -> Result<()> {
+        log::debug!("{:#?}", card);
         let id = Uuid::new_v4().to_string();
+        let search_query = target.search_query_key();
+        let (starting_price, currency) = match &card.starting_price {
+            Some(price) => (Some(price.amount), price.currency.clone()),
+            None => (None, None),
+        };
+        let badges = card.badges.join(", ");
         sqlx::query!(
-            "INSERT INTO gigs(id, url, title, description, page) VALUES($1, $2, $3, $4, $5)",
+            "INSERT INTO gig_listings(id, url, category, menu, search_query, page, position, rating, review_count, starting_price, currency, badges)
+            VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
             id,
-            gig.url,
+            card.url,
+            target.category,
+            target.menu,
+            search_query,
+            card.page,
+            card.position,
+            card.rating,
+            card.review_count,
+            starting_price,
+            currency,
+            badges
+        )
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn save_tags(conn: &mut SqliteConnection, gig_id: &str, tags: Vec<String>) -> Result<()> {
+        log::debug!("{:#?}", tags);
+        sqlx::query!("DELETE FROM gig_tags WHERE gig_id = $1", gig_id)
+            .execute(&mut *conn)
+            .await?;
+        for (position, tag) in tags.into_iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                "INSERT INTO gig_tags(gig_id, position, tag) VALUES($1, $2, $3)",
+                gig_id,
+                position,
+                tag
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn save_faq(conn: &mut SqliteConnection, gig_id: &str, faq: Vec<FaqData>) -> Result<()> {
+        log::debug!("{:#?}", faq);
+        sqlx::query!("DELETE FROM gig_faq WHERE gig_id = $1", gig_id)
+            .execute(&mut *conn)
+            .await?;
+        for (position, entry) in faq.into_iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                "INSERT INTO gig_faq(gig_id, position, question, answer) VALUES($1, $2, $3, $4)",
+                gig_id,
+                position,
+                entry.question,
+                entry.answer
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn save_attributes(
+        conn: &mut SqliteConnection,
+        gig_id: &str,
+        attributes: Vec<AttributeData>,
+    ) -> Result<()> {
+        log::debug!("{:#?}", attributes);
+        sqlx::query!("DELETE FROM gig_attributes WHERE gig_id = $1", gig_id)
+            .execute(&mut *conn)
+            .await?;
+        for attribute in attributes {
+            for value in attribute.values {
+                sqlx::query!(
+                    "INSERT OR IGNORE INTO gig_attributes(gig_id, name, value) VALUES($1, $2, $3)",
+                    gig_id,
+                    attribute.name,
+                    value
+                )
+                .execute(&mut *conn)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Keys a review by its reviewer, country and text. Identical reviews by the same reviewer
+    /// are told apart by their order of occurrence. The displayed date is relative ("2 weeks
+    /// ago") and would change the key of a review on every re-scrape, so it is left out.
+    fn review_key(review: &ReviewData, occurrence: u32) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(review.reviewer.as_deref().unwrap_or_default());
+        hasher.update([0]);
+        hasher.update(review.country.as_deref().unwrap_or_default());
+        hasher.update([0]);
+        hasher.update(&review.text);
+        hasher.update([0]);
+        hasher.update(occurrence.to_be_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Inserts new reviews and updates the ones seen before; reviews are never removed.
+    async fn save_reviews(
+        conn: &mut SqliteConnection,
+        gig_id: &str,
+        reviews: Vec<ReviewData>,
+    ) -> Result<()> {
+        log::debug!("{:#?}", reviews);
+        let mut occurrences = HashMap::new();
+        for review in reviews {
+            let occurrence = occurrences
+                .entry((
+                    review.reviewer.clone(),
+                    review.country.clone(),
+                    review.text.clone(),
+                ))
+                .or_insert(0u32);
+            let review_key = Self::review_key(&review, *occurrence);
+            *occurrence += 1;
+
+            let id = Uuid::new_v4().to_string();
+            sqlx::query!(
+                "INSERT INTO reviews(id, gig_id, review_key, reviewer, country, rating, review_date, text, price_range, duration, seller_response)
+                VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                ON CONFLICT(gig_id, review_key) DO UPDATE SET
+                    country = excluded.country,
+                    rating = excluded.rating,
+                    review_date = excluded.review_date,
+                    price_range = excluded.price_range,
+                    duration = excluded.duration,
+                    seller_response = excluded.seller_response,
+                    last_seen_at = CURRENT_TIMESTAMP",
+                id,
+                gig_id,
+                review_key,
+                review.reviewer,
+                review.country,
+                review.rating,
+                review.date,
+                review.text,
+                review.price_range,
+                review.duration,
+                review.seller_response
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Inserts or updates the seller and returns its id.
+    async fn save_seller(conn: &mut SqliteConnection, seller: SellerData) -> Result<String> {
+        log::debug!("{:#?}", seller);
+        let new_id = Uuid::new_v4().to_string();
+        let languages = seller.languages.join(", ");
+        let id = sqlx::query_scalar!(
+            "INSERT INTO sellers(id, username, display_name, level, country, member_since, avg_response_time, last_delivery, languages)
+            VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT(username) DO UPDATE SET
+                display_name = excluded.display_name,
+                level = excluded.level,
+                country = excluded.country,
+                member_since = excluded.member_since,
+                avg_response_time = excluded.avg_response_time,
+                last_delivery = excluded.last_delivery,
+                languages = excluded.languages,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING id",
+            new_id,
+            seller.username,
+            seller.display_name,
+            seller.level,
+            seller.country,
+            seller.member_since,
+            seller.avg_response_time,
+            seller.last_delivery,
+            languages
+        )
+        .fetch_one(conn)
+        .await?;
+        Ok(id)
+    }
+
+    /// Saves the gig and everything scraped with it in one transaction. A gig saved before (e.g.
+    /// one marked incomplete) keeps its id and has its details replaced. A gig saved as incomplete
+    /// is visited again later.
+    async fn save(
+        &self,
+        gig: GigData,
+        visuals: Vec<DownloadedVisual>,
+        complete: bool,
+    ) -> Result<()> {
+        let mut tx = self.db.begin().await?;
+        let status = match complete {
+            true => "complete",
+            false => "incomplete",
+        };
+
+        let seller_id = Self::save_seller(&mut tx, gig.seller).await?;
+        let search_query = gig.target.search_query_key();
+
+        let new_id = Uuid::new_v4().to_string();
+        let description_text = html_text::to_plain_text(&gig.description);
+        let description_markdown = html_text::to_markdown(&gig.description);
+        let id = sqlx::query_scalar!(
+            "INSERT INTO gigs(id, url, title, description, description_text, description_markdown, page, category, menu, search_query, status, seller_id)
+            VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT(url) DO UPDATE SET
+                title = excluded.title,
+                description = excluded.description,
+                description_text = excluded.description_text,
+                description_markdown = excluded.description_markdown,
+                page = excluded.page,
+                category = excluded.category,
+                menu = excluded.menu,
+                search_query = excluded.search_query,
+                status = excluded.status,
+                seller_id = excluded.seller_id
+            RETURNING id",
+            new_id,
+            gig.card.url,
             gig.title,
             gig.description,
-            gig.page
+            description_text,
+            description_markdown,
+            gig.card.page,
+            gig.target.category,
+            gig.target.menu,
+            search_query,
+            status,
+            seller_id
         )
-        .execute(&self.db)
+        .fetch_one(&mut *tx)
         .await?;
-        self.save_visuals(id, gig.visuals).await?;
+
+        // Earlier failures no longer count once the gig has been scraped completely.
+        if complete {
+            sqlx::query!("DELETE FROM failed_gigs WHERE url = $1", gig.card.url)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        Self::save_visuals(&mut tx, &id, visuals).await?;
+        Self::save_packages(&mut tx, &id, gig.packages).await?;
+        Self::save_reviews(&mut tx, &id, gig.reviews).await?;
+        Self::save_tags(&mut tx, &id, gig.tags).await?;
+        Self::save_faq(&mut tx, &id, gig.faq).await?;
+        Self::save_attributes(&mut tx, &id, gig.attributes).await?;
+
+        tx.commit().await?;
 
         Ok(())
     }
 
-    async fn last_scraped_page(&self) -> Result<u32> {
-        let fetch_result = sqlx::query!("SELECT page FROM gigs ORDER BY page DESC LIMIT 1")
-            .fetch_one(&self.db)
-            .await;
+    async fn scrape_progress(&self, target: &ScrapeTarget) -> Result<ScrapeProgress> {
+        let search_query = target.search_query_key();
+        let fetch_result = sqlx::query!(
+            r#"SELECT last_page, last_gig_index, page_finished_at IS NOT NULL AS "page_finished!: bool"
+            FROM scrape_progress WHERE category = $1 AND menu = $2 AND search_query = $3"#,
+            target.category,
+            target.menu,
+            search_query
+        )
+        .fetch_one(&self.db)
+        .await;
         match fetch_result {
-            Err(sqlx::Error::RowNotFound) => Ok(1),
-            Ok(record) => Ok(record.page as u32),
+            Err(sqlx::Error::RowNotFound) => Ok(ScrapeProgress::default()),
+            Ok(record) => Ok(ScrapeProgress {
+                last_page: record.last_page as u32,
+                last_gig_index: record.last_gig_index.map(|idx| idx as u32),
+                page_finished: record.page_finished,
+            }),
             Err(e) => Err(e.into()),
         }
     }
+
+    async fn record_scraped_gig(
+        &self,
+        target: &ScrapeTarget,
+        page: u32,
+        gig_index: u32,
+    ) -> Result<()> {
+        let search_query = target.search_query_key();
+        sqlx::query!(
+            "INSERT INTO scrape_progress(category, menu, search_query, last_page, last_gig_index)
+            VALUES($1, $2, $3, $4, $5)
+            ON CONFLICT(category, menu, search_query) DO UPDATE SET
+                last_page = excluded.last_page,
+                last_gig_index = excluded.last_gig_index,
+                page_finished_at = NULL",
+            target.category,
+            target.menu,
+            search_query,
+            page,
+            gig_index
+        )
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Passes over the listing card at the position on the page of the target, which could not be
+    /// read and so names no gig to count the failure against, by moving the progress past it.
+    async fn skip_failed_card(
+        &self,
+        target: &ScrapeTarget,
+        page: u32,
+        position: u32,
+        error: &anyhow::Error,
+    ) -> Result<()> {
+        log::error!("Skipping gig card {page}/{position} of {target}: {error}");
+        self.record_scraped_gig(target, page, position).await
+    }
+
+    async fn log_skipped_card(
+        &self,
+        target: &ScrapeTarget,
+        gig_url: &str,
+        card: &GigCardData,
+        reason: &SkipReason,
+    ) -> Result<()> {
+        log::info!("Skipping gig card {gig_url}: {reason}");
+        let search_query = target.search_query_key();
+        let reason = reason.to_string();
+        sqlx::query!(
+            "INSERT INTO skipped_gig_cards(url, category, menu, search_query, page, position, reason)
+            VALUES($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT(url) DO UPDATE SET
+                category = excluded.category,
+                menu = excluded.menu,
+                search_query = excluded.search_query,
+                page = excluded.page,
+                position = excluded.position,
+                reason = excluded.reason,
+                skipped_at = CURRENT_TIMESTAMP",
+            gig_url,
+            target.category,
+            target.menu,
+            search_query,
+            card.page,
+            card.position,
+            reason
+        )
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn finish_page(&self, target: &ScrapeTarget, page: u32) -> Result<()> {
+        let search_query = target.search_query_key();
+        sqlx::query!(
+            "INSERT INTO scrape_progress(category, menu, search_query, last_page, page_finished_at)
+            VALUES($1, $2, $3, $4, CURRENT_TIMESTAMP)
+            ON CONFLICT(category, menu, search_query) DO UPDATE SET
+                last_gig_index = CASE
+                    WHEN last_page = excluded.last_page THEN last_gig_index
+                    ELSE NULL
+                END,
+                last_page = excluded.last_page,
+                page_finished_at = excluded.page_finished_at",
+            target.category,
+            target.menu,
+            search_query,
+            page
+        )
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
 }
 
 struct MenuItemPage<'a> {
     tab: &'a Arc<Tab>,
     store: Arc<ScrapedGigsStore>,
+    filter: Arc<GigFilter>,
 }
 
 enum GetTargetPageAnchorElement<'a> {
@@ -264,8 +1236,8 @@ enum GetTargetPageAnchorElement<'a> {
 }
 
 impl<'a> MenuItemPage<'a> {
-    fn new(tab: &'a Arc<Tab>, store: Arc<ScrapedGigsStore>) -> Self {
-        Self { tab, store }
+    fn new(tab: &'a Arc<Tab>, store: Arc<ScrapedGigsStore>, filter: Arc<GigFilter>) -> Self {
+        Self { tab, store, filter }
     }
 
     fn gig_cards_selector() -> &'static str {
@@ -275,32 +1247,68 @@ impl<'a> MenuItemPage<'a> {
     fn get_gig_cards(&'a self) -> Result<Vec<Element<'a>>> {
         let elements_selector = Self::gig_cards_selector();
         log::info!("Wait for elements: {elements_selector}");
-        self.tab.wait_for_elements(elements_selector)
+        self.tab
+            .wait_for_elements(elements_selector)
+            .map_err(ScrapeError::not_found(
+                elements_selector,
+                "on the listing page",
+            ))
     }
 
-    fn get_gig_orders_count<'b>(gig_card: &Element<'b>) -> Result<usize> {
-        log::info!("Find element: [ref:gig_card] .orca-rating > span");
-        let span = gig_card.find_element(".orca-rating > span")?;
-        log::info!("Get inner text: [ref:gig_card] .orca-rating > span");
-        let orders_count: usize = span
-            .get_inner_text()?
-            .replace(['(', ')'], "")
-            .trim()
-            .parse()
-            .unwrap_or(1000);
-        Ok(orders_count)
+    fn gig_card_badges_selector() -> &'static str {
+        ".gig-badges .badge"
     }
 
-    fn gig_order_count_gt_threshold<'b>(gig_card: &Element<'b>) -> Result<bool> {
-        let orders_count = Self::get_gig_orders_count(gig_card)?;
-        Ok(orders_count >= 100)
+    fn get_gig_card_data<'b>(
+        gig_card: &Element<'b>,
+        page: u32,
+        position: u32,
+    ) -> Result<GigCardData> {
+        let url = Self::get_gig_url(gig_card)?;
+        let title = ElementText::find(gig_card, "h3")?;
+        let seller_level = ElementText::find(gig_card, ".seller-level")?;
+        let rating = ElementText::find(gig_card, ".orca-rating .rating-score")?
+            .and_then(|rating| rating.parse().ok());
+        let review_count = ElementText::find(gig_card, ".orca-rating > span")?.and_then(|count| {
+            let review_count = ReviewCountParser::parse(&count);
+            if review_count.is_none() {
+                log::warn!("Could not parse review count '{count}'");
+            }
+            review_count
+        });
+        let starting_price = ElementText::find(gig_card, "footer .price")?
+            .and_then(|price| PriceParser::parse(&price));
+
+        let element_selector = Self::gig_card_badges_selector();
+        log::info!("Find elements: [ref:gig_card] {element_selector}");
+        let badge_els = gig_card.find_elements(element_selector).unwrap_or_default();
+        let mut badges = Vec::with_capacity(badge_els.len());
+        for badge_el in badge_els {
+            log::info!("Get inner text: [ref:gig_card] {element_selector}");
+            let badge = badge_el.get_inner_text()?.trim().to_owned();
+            if !badge.is_empty() {
+                badges.push(badge);
+            }
+        }
+
+        Ok(GigCardData {
+            url,
+            page,
+            position,
+            title,
+            seller_level,
+            rating,
+            review_count,
+            starting_price,
+            badges,
+        })
     }
 
     fn visit_gig<'b>(&self, gig_card: Element<'b>) -> Result<()> {
         log::info!("Find element: [ref:gig_card] a");
         let anchor = gig_card.find_element("a")?;
         anchor.click()?;
-        self.tab.wait_until_navigated()?;
+        NavigationWaiter::wait(self.tab, "gig page")?;
         Ok(())
     }
 
@@ -308,9 +1316,12 @@ impl<'a> MenuItemPage<'a> {
         log::info!("Find element: [ref:gig_card] a");
         let anchor = gig_card.find_element("a")?;
         log::info!("Get attribute: [el:gig_card] a");
-        let href = anchor.get_attribute_value("href")?.ok_or(anyhow!(
-            "Element ([el:gig_card] a) does not have a 'href' attribute",
-        ))?;
+        let href = anchor
+            .get_attribute_value("href")?
+            .ok_or(ScrapeError::AttributeMissing {
+                selector: "[el:gig_card] a".to_owned(),
+                attribute: "href".to_owned(),
+            })?;
         let stripped_url = QueryPathStripper::strip(&href);
         UrlNormalizer::normalize(stripped_url)
     }
@@ -322,10 +1333,16 @@ impl<'a> MenuItemPage<'a> {
     async fn next_gigs_page(&self) -> Result<()> {
         let element_selector = Self::next_gigs_page_btn_selector();
         log::info!("Wait for element: {element_selector}");
-        let next_page_btn = self.tab.wait_for_element(element_selector)?;
+        let next_page_btn =
+            self.tab
+                .wait_for_element(element_selector)
+                .map_err(ScrapeError::not_found(
+                    element_selector,
+                    "on the listing page",
+                ))?;
         log::info!("Click: {element_selector}");
         next_page_btn.click()?;
-        self.tab.wait_until_navigated()?;
+        NavigationWaiter::wait(self.tab, "next listing page")?;
         sleep(Duration::from_secs(BTN_CLICK_WAIT_SECS)).await;
         Ok(())
     }
@@ -346,14 +1363,23 @@ impl<'a> MenuItemPage<'a> {
 
         let description = remote_object
             .description
-            .ok_or(anyhow!("RemoteObject.description is None"))?;
+            .ok_or(ScrapeError::SelectorNotFound {
+                selector: "[fn:get_parent_element]".to_owned(),
+                context: "as the element has no parent".to_owned(),
+            })?;
         self.tab.find_element(&description)
     }
 
     fn get_pagination_element(&'a self) -> Result<Element<'a>> {
         let element_selector = Self::next_gigs_page_btn_selector();
         log::info!("Wait for element: {element_selector}");
-        let next_page_btn = self.tab.wait_for_element(element_selector)?;
+        let next_page_btn =
+            self.tab
+                .wait_for_element(element_selector)
+                .map_err(ScrapeError::not_found(
+                    element_selector,
+                    "for the pagination",
+                ))?;
         self.get_parent_element(next_page_btn)
     }
 
@@ -383,7 +1409,7 @@ impl<'a> MenuItemPage<'a> {
             }
         }
 
-        let last_item = last_item.ok_or(anyhow!("No pagination with numeric page number found"))?;
+        let last_item = last_item.ok_or(ScrapeError::PaginationExhausted { page: target_page })?;
         let last_item_page = get_pagination_page(&last_item)?.unwrap(); //infallible
         match last_item_page == target_page {
             true => Ok(GetTargetPageAnchorElement::Target(last_item)),
@@ -394,10 +1420,16 @@ impl<'a> MenuItemPage<'a> {
     async fn prev_gigs_page(&self) -> Result<()> {
         let element_selector = Self::prev_gigs_page_btn_selector();
         log::info!("Wait for element: {element_selector}");
-        let prev_page_btn = self.tab.wait_for_element(element_selector)?;
+        let prev_page_btn =
+            self.tab
+                .wait_for_element(element_selector)
+                .map_err(ScrapeError::not_found(
+                    element_selector,
+                    "on the listing page",
+                ))?;
         log::info!("Click: {element_selector}");
         prev_page_btn.click()?;
-        self.tab.wait_until_navigated()?;
+        NavigationWaiter::wait(self.tab, "previous listing page")?;
         sleep(Duration::from_secs(BTN_CLICK_WAIT_SECS)).await;
         Ok(())
     }
@@ -416,9 +1448,13 @@ impl<'a> MenuItemPage<'a> {
         Ok(page_count)
     }
 
-    async fn go_to_page(&self, page: u32) -> Result<()> {
+    /// Navigates to the last page recorded in the target's progress.
+    async fn go_to_page(&self, progress: &ScrapeProgress) -> Result<()> {
+        let page = progress.last_page;
+        // The page a pagination link was last clicked on; clicking it again would not get further.
+        let mut clicked_on_page = None;
         loop {
-            while ErrorPageDetector::process(self.tab).await? {}
+            ErrorPageDetector::wait_until_cleared(self.tab).await?;
             let current_page = self.get_page_count()?;
             log::debug!("current page: {current_page}");
             match current_page.cmp(&page) {
@@ -427,6 +1463,10 @@ impl<'a> MenuItemPage<'a> {
                     self.prev_gigs_page().await?;
                 }
                 Ordering::Less => {
+                    if clicked_on_page == Some(current_page) {
+                        return Err(ScrapeError::PaginationExhausted { page }.into());
+                    }
+                    clicked_on_page = Some(current_page);
                     match self.get_target_page_anchor(page)? {
                         GetTargetPageAnchorElement::Target(target_page_a) => {
                             log::info!("Click: [ref:target_page_a]");
@@ -437,45 +1477,121 @@ impl<'a> MenuItemPage<'a> {
                             last_page_a.click()?;
                         }
                     }
-                    self.tab.wait_until_navigated()?;
+                    NavigationWaiter::wait(self.tab, &format!("listing page {page}"))?;
                     sleep(Duration::from_secs(BTN_CLICK_WAIT_SECS)).await;
                 }
             }
         }
     }
 
-    async fn go_to_new_gig(&self) -> Result<u32> {
+    fn has_next_gigs_page(&self) -> bool {
+        let element_selector = Self::next_gigs_page_btn_selector();
+        log::info!("Find element: {element_selector}");
+        self.tab.find_element(element_selector).is_ok()
+    }
+
+    /// Opens the first unscraped gig from the current page onwards and returns its card, or `None`
+    /// once the listing has no further pages. Cards are recorded in the listings unless they are
+    /// in `listed_cards` already, as the same page is walked again for every gig.
+    async fn go_to_new_gig(
+        &self,
+        target: &ScrapeTarget,
+        progress: &ScrapeProgress,
+        listed_cards: &mut HashSet<(String, u32, u32)>,
+    ) -> Result<Option<GigCardData>> {
         loop {
+            let page = self.get_page_count()?;
+            let (page_finished, last_gig_index) = match page == progress.last_page {
+                true => (progress.page_finished, progress.last_gig_index),
+                false => (false, None),
+            };
             let gig_cards = self.get_gig_cards()?;
-            for (idx, card) in gig_cards.into_iter().enumerate() {
-                log::info!("Gig index: {idx}");
-                if Self::gig_order_count_gt_threshold(&card)? {
-                    let gig_url = Self::get_gig_url(&card)?;
+            if page_finished {
+                log::info!("Page {page} already finished");
+            } else {
+                for (idx, card) in gig_cards.into_iter().enumerate() {
+                    log::info!("Gig index: {idx}");
+                    let idx = idx as u32;
+                    if last_gig_index.is_some_and(|last_idx| idx <= last_idx) {
+                        continue;
+                    }
+                    let card_data = match Self::get_gig_card_data(&card, page, idx) {
+                        Ok(card_data) => card_data,
+                        // Without its data the card has no gig to pass over or to blame, and
+                        // walking the page again would only fail on it again.
+                        Err(e) if error::is_caused_by_gig(&e) => {
+                            self.store.skip_failed_card(target, page, idx, &e).await?;
+                            continue;
+                        }
+                        Err(e) => return Err(e),
+                    };
+                    let gig_url = card_data.url.clone();
                     log::debug!("Gig URL: {gig_url}");
+                    if listed_cards.insert((target.to_string(), page, idx)) {
+                        self.store.record_listing(target, &card_data).await?;
+                    }
+                    if let Err(reason) = self.filter.check(&card_data) {
+                        self.store
+                            .log_skipped_card(target, &gig_url, &card_data, &reason)
+                            .await?;
+                        continue;
+                    }
                     log::debug!("is scraped: {}", self.store.is_scraped(&gig_url).await?);
                     if self.store.is_scraped(&gig_url).await? {
                         log::debug!("continuing...");
                         continue;
                     }
                     self.visit_gig(card)?;
-                    return self.get_page_count();
+                    return Ok(Some(card_data));
                 }
+                self.store.finish_page(target, page).await?;
+            }
+            if !self.has_next_gigs_page() {
+                return Ok(None);
             }
             self.next_gigs_page().await?;
         }
     }
 }
 
+/// Where a gig page takes the URLs of its gallery media from.
+enum GigMediaSource {
+    Gallery,
+    Network {
+        captured_media: CapturedMediaLog,
+        gallery_fallback: bool,
+    },
+}
+
 struct GigPage<'a> {
     tab: &'a Arc<Tab>,
-    page: u32,
+    target: ScrapeTarget,
+    card: GigCardData,
+    review_limit: usize,
+    media_source: GigMediaSource,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 enum SlideType {
     Video,
     Image,
     Pdf,
+    Audio,
+    /// A slide whose class matches none of the known kinds; holds the class.
+    Unknown(String),
+}
+
+impl SlideType {
+    /// The slide type a file of the given MIME type is shown as, if any.
+    fn from_mime_type(mime_type: &str) -> Option<Self> {
+        match mime_type.split('/').next()? {
+            "image" => Some(SlideType::Image),
+            "video" => Some(SlideType::Video),
+            "audio" => Some(SlideType::Audio),
+            _ if mime_type == "application/pdf" => Some(SlideType::Pdf),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for SlideType {
@@ -484,6 +1600,8 @@ impl std::fmt::Display for SlideType {
             SlideType::Image => "image",
             SlideType::Video => "video",
             SlideType::Pdf => "pdf",
+            SlideType::Audio => "audio",
+            SlideType::Unknown(_) => "unknown",
         };
         f.write_str(v)
     }
@@ -493,39 +1611,179 @@ impl std::fmt::Display for SlideType {
 struct VisualData {
     url: String,
     typ: SlideType,
+    position: u32,
 }
 
-struct GigData {
-    url: String,
-    title: String,
-    description: String,
-    visuals: Vec<VisualData>,
-    page: u32,
+/// A file put into the media store.
+#[derive(Debug)]
+struct DownloadedFile {
+    /// The storage URI of the file.
+    uri: String,
+    mime_type: Option<String>,
+    byte_size: u64,
+    sha256: String,
+    video: Option<ProcessedVideo>,
+    image_hashes: Option<ImageHashes>,
 }
 
-impl<'a> GigPage<'a> {
-    fn new(tab: &'a Arc<Tab>, page: u32) -> Self {
-        Self { tab, page }
-    }
+/// A downloaded image with its perceptual hashes.
+struct HashedImage {
+    id: String,
+    gig_id: String,
+    gig_url: String,
+    storage_uri: Option<String>,
+    hashes: ImageHashes,
+}
 
-    fn title_selector() -> &'static str {
-        "#main-wrapper .gig-page .gig-overview h1"
-    }
+/// The result of post-processing a downloaded video.
+#[derive(Debug)]
+struct ProcessedVideo {
+    metadata: VideoMetadata,
+    /// The storage URI of the poster frame.
+    poster_uri: Option<String>,
+}
 
-    fn about_selector() -> &'static str {
-        "#main-wrapper .gig-page .description-content"
-    }
+/// A scraped visual together with the outcome of downloading it.
+#[derive(Debug)]
+struct DownloadedVisual {
+    visual: VisualData,
+    outcome: Result<DownloadedFile, DownloadFailure>,
+}
 
-    fn get_url(&self) -> Result<String> {
-        let url = self.tab.get_url();
-        let stripped_url = QueryPathStripper::strip(&url);
-        UrlNormalizer::normalize(stripped_url)
-    }
+/// Why a file could not be downloaded, after all retries.
+#[derive(Debug)]
+struct DownloadFailure {
+    error: String,
+    /// The status of the last response, if the server answered with an error status.
+    http_status: Option<u16>,
+}
+
+/// A saved visual whose download failed.
+struct FailedVisual {
+    id: String,
+    source_url: String,
+}
+
+#[derive(Debug)]
+enum PackageRevisions {
+    Limited(u32),
+    Unlimited,
+}
+
+#[derive(Debug)]
+struct PackageFeature {
+    name: String,
+    included: bool,
+}
+
+/// A pricing tier (Basic/Standard/Premium) from the gig's package table.
+#[derive(Debug)]
+struct PackageData {
+    tier: String,
+    title: Option<String>,
+    description: Option<String>,
+    price: Option<Price>,
+    delivery_days: Option<u32>,
+    revisions: Option<PackageRevisions>,
+    features: Vec<PackageFeature>,
+}
+
+/// The seller block ("About the seller") of a gig page.
+#[derive(Debug)]
+struct SellerData {
+    username: String,
+    display_name: Option<String>,
+    level: Option<String>,
+    country: Option<String>,
+    member_since: Option<String>,
+    avg_response_time: Option<String>,
+    last_delivery: Option<String>,
+    languages: Vec<String>,
+}
+
+/// A buyer review from the gig page. The date is kept as displayed, e.g. "2 weeks ago".
+#[derive(Debug)]
+struct ReviewData {
+    reviewer: Option<String>,
+    country: Option<String>,
+    rating: Option<f64>,
+    date: Option<String>,
+    text: String,
+    price_range: Option<String>,
+    duration: Option<String>,
+    seller_response: Option<String>,
+}
+
+#[derive(Debug)]
+struct FaqData {
+    question: String,
+    answer: Option<String>,
+}
+
+/// A metadata attribute of the gig, e.g. "Programming language" with its values.
+#[derive(Debug)]
+struct AttributeData {
+    name: String,
+    values: Vec<String>,
+}
+
+struct GigData {
+    /// The URL the gig page ended up on. Gigs are stored under the URL of their card, which
+    /// failures and revisits are keyed by too, so that a redirect does not split them.
+    url: String,
+    title: String,
+    description: String,
+    visuals: Vec<VisualData>,
+    packages: Vec<PackageData>,
+    seller: SellerData,
+    reviews: Vec<ReviewData>,
+    tags: Vec<String>,
+    faq: Vec<FaqData>,
+    attributes: Vec<AttributeData>,
+    /// Why the gallery could not be collected; the gig is then saved as incomplete.
+    gallery_error: Option<String>,
+    target: ScrapeTarget,
+    card: GigCardData,
+}
+
+impl<'a> GigPage<'a> {
+    fn new(
+        tab: &'a Arc<Tab>,
+        target: ScrapeTarget,
+        card: GigCardData,
+        review_limit: usize,
+        media_source: GigMediaSource,
+    ) -> Self {
+        Self {
+            tab,
+            target,
+            card,
+            review_limit,
+            media_source,
+        }
+    }
+
+    fn title_selector() -> &'static str {
+        "#main-wrapper .gig-page .gig-overview h1"
+    }
+
+    fn about_selector() -> &'static str {
+        "#main-wrapper .gig-page .description-content"
+    }
+
+    fn get_url(&self) -> Result<String> {
+        let url = self.tab.get_url();
+        let stripped_url = QueryPathStripper::strip(&url);
+        UrlNormalizer::normalize(stripped_url)
+    }
 
     fn get_about(&self) -> Result<String> {
         let element_selector = Self::about_selector();
         log::info!("Find element: {element_selector}");
-        let description_el = self.tab.find_element(element_selector)?;
+        let description_el = self
+            .tab
+            .find_element(element_selector)
+            .map_err(ScrapeError::not_found(element_selector, "on the gig page"))?;
         log::info!("Get content: {element_selector}");
         let description = description_el.get_content()?;
         Ok(description)
@@ -534,12 +1792,352 @@ impl<'a> GigPage<'a> {
     fn get_title(&self) -> Result<String> {
         let element_selector = Self::title_selector();
         log::info!("Find element: {element_selector}");
-        let title_el = self.tab.find_element(element_selector)?;
+        let title_el = self
+            .tab
+            .find_element(element_selector)
+            .map_err(ScrapeError::not_found(element_selector, "on the gig page"))?;
         log::info!("Get inner text: {element_selector}");
         let title = title_el.get_inner_text()?;
         Ok(title)
     }
 
+    fn package_tabs_selector() -> &'static str {
+        "#main-wrapper .gig-page .packages-tabs .nav-container label"
+    }
+
+    fn package_content_selector() -> &'static str {
+        "#main-wrapper .gig-page .package-content"
+    }
+
+    fn get_package(&self, tier: String) -> Result<PackageData> {
+        let element_selector = Self::package_content_selector();
+        log::info!("Find element: {element_selector}");
+        let package_el = self
+            .tab
+            .find_element(element_selector)
+            .map_err(ScrapeError::not_found(element_selector, "on the gig page"))?;
+
+        let title = ElementText::find(&package_el, "header h3 .title")?;
+        let description = ElementText::find(&package_el, "header p")?;
+        let price = ElementText::find(&package_el, "header .price")?
+            .and_then(|price| PriceParser::parse(&price));
+        let delivery_days = ElementText::find(&package_el, ".additional-info .delivery-wrapper")?
+            .and_then(|delivery| NumberParser::first_u32(&delivery));
+        let revisions = ElementText::find(&package_el, ".additional-info .revisions-wrapper")?
+            .and_then(
+                |revisions| match revisions.to_lowercase().contains("unlimited") {
+                    true => Some(PackageRevisions::Unlimited),
+                    false => NumberParser::first_u32(&revisions).map(PackageRevisions::Limited),
+                },
+            );
+
+        log::info!("Find elements: {element_selector} ul.features li");
+        let feature_els = package_el
+            .find_elements("ul.features li")
+            .unwrap_or_default();
+        let mut features = Vec::with_capacity(feature_els.len());
+        for feature_el in feature_els {
+            log::info!("Get inner text: {element_selector} ul.features li");
+            let name = feature_el.get_inner_text()?.trim().to_owned();
+            log::info!("Get attribute value: {element_selector} ul.features li.class");
+            let class = feature_el.get_attribute_value("class")?.unwrap_or_default();
+            features.push(PackageFeature {
+                name,
+                included: class.contains("included"),
+            });
+        }
+
+        Ok(PackageData {
+            tier,
+            title,
+            description,
+            price,
+            delivery_days,
+            revisions,
+            features,
+        })
+    }
+
+    /// Collects every package tier, switching between the package tabs when the gig offers more
+    /// than one.
+    async fn get_packages(&self) -> Result<Vec<PackageData>> {
+        let element_selector = Self::package_tabs_selector();
+        log::info!("Find elements: {element_selector}");
+        let tab_count = self
+            .tab
+            .find_elements(element_selector)
+            .map(|tabs| tabs.len())
+            .unwrap_or(0);
+        if tab_count == 0 {
+            // Gigs offering a single package show it without tabs; some show no package at all.
+            let element_selector = Self::package_content_selector();
+            log::info!("Find element: {element_selector}");
+            if self.tab.find_element(element_selector).is_err() {
+                log::warn!("Gig has no packages");
+                return Ok(Vec::new());
+            }
+            return Ok(vec![self.get_package("Basic".to_owned())?]);
+        }
+
+        let mut packages = Vec::with_capacity(tab_count);
+        for idx in 0..tab_count {
+            // Look the tabs up again since clicking a tab re-renders the package table.
+            log::info!("Find elements: {element_selector}");
+            let package_tab = self
+                .tab
+                .find_elements(element_selector)
+                .map_err(ScrapeError::not_found(element_selector, "on the gig page"))?
+                .into_iter()
+                .nth(idx)
+                .ok_or(ScrapeError::SelectorNotFound {
+                    selector: format!("{element_selector} {idx}"),
+                    context: "after clicking the previous package tab".to_owned(),
+                })?;
+            log::info!("Get inner text: {element_selector} {idx}");
+            let tier = package_tab.get_inner_text()?.trim().to_owned();
+            log::info!("Click: {element_selector} {idx}");
+            package_tab.click()?;
+            sleep(Duration::from_secs(BTN_CLICK_WAIT_SECS)).await;
+            packages.push(self.get_package(tier)?);
+        }
+        Ok(packages)
+    }
+
+    fn seller_card_selector() -> &'static str {
+        "#main-wrapper .gig-page .seller-card"
+    }
+
+    fn get_seller(&self) -> Result<SellerData> {
+        let element_selector = Self::seller_card_selector();
+        log::info!("Find element: {element_selector}");
+        let seller_el = self
+            .tab
+            .find_element(element_selector)
+            .map_err(ScrapeError::not_found(element_selector, "on the gig page"))?;
+
+        log::info!("Find element: {element_selector} a.seller-link");
+        let seller_link =
+            seller_el
+                .find_element("a.seller-link")
+                .map_err(ScrapeError::not_found(
+                    &format!("{element_selector} a.seller-link"),
+                    "on the gig page",
+                ))?;
+        log::info!("Get attribute: {element_selector} a.seller-link.href");
+        let href =
+            seller_link
+                .get_attribute_value("href")?
+                .ok_or(ScrapeError::AttributeMissing {
+                    selector: format!("{element_selector} a.seller-link"),
+                    attribute: "href".to_owned(),
+                })?;
+        let profile_url = Url::parse(&UrlNormalizer::normalize(QueryPathStripper::strip(&href))?)?;
+        let username = profile_url
+            .path_segments()
+            .and_then(|mut segments| segments.find(|segment| !segment.is_empty()))
+            .ok_or(ScrapeError::SelectorNotFound {
+                selector: format!("{element_selector} a.seller-link"),
+                context: format!("with a username in '{href}'"),
+            })?
+            .to_owned();
+        log::info!("Get inner text: {element_selector} a.seller-link");
+        let display_name = Some(seller_link.get_inner_text()?.trim().to_owned())
+            .filter(|display_name| !display_name.is_empty());
+        let level = ElementText::find(&seller_el, ".seller-level")?;
+
+        let mut seller = SellerData {
+            username,
+            display_name,
+            level,
+            country: None,
+            member_since: None,
+            avg_response_time: None,
+            last_delivery: None,
+            languages: Vec::new(),
+        };
+
+        log::info!("Find elements: {element_selector} .user-stats li");
+        let stat_els = seller_el
+            .find_elements(".user-stats li")
+            .unwrap_or_default();
+        for stat_el in stat_els {
+            let Some(value) = ElementText::find(&stat_el, "strong")? else {
+                continue;
+            };
+            log::info!("Get inner text: {element_selector} .user-stats li");
+            let label = stat_el
+                .get_inner_text()?
+                .replace(&value, "")
+                .trim()
+                .to_lowercase();
+            match label.as_str() {
+                "from" => seller.country = Some(value),
+                "member since" => seller.member_since = Some(value),
+                "avg. response time" => seller.avg_response_time = Some(value),
+                "last delivery" => seller.last_delivery = Some(value),
+                "languages" => {
+                    seller.languages = value
+                        .split(',')
+                        .map(|language| language.trim().to_owned())
+                        .filter(|language| !language.is_empty())
+                        .collect()
+                }
+                _ => log::debug!("Unknown seller stat: {label}"),
+            }
+        }
+
+        Ok(seller)
+    }
+
+    fn tags_selector() -> &'static str {
+        "#main-wrapper .gig-page .gig-tags-container li"
+    }
+
+    fn get_tags(&self) -> Result<Vec<String>> {
+        let element_selector = Self::tags_selector();
+        log::info!("Find elements: {element_selector}");
+        let tag_els = self.tab.find_elements(element_selector).unwrap_or_default();
+        let mut tags = Vec::with_capacity(tag_els.len());
+        for tag_el in tag_els {
+            log::info!("Get inner text: {element_selector}");
+            let tag = tag_el.get_inner_text()?.trim().to_owned();
+            if !tag.is_empty() {
+                tags.push(tag);
+            }
+        }
+        Ok(tags)
+    }
+
+    fn faq_selector() -> &'static str {
+        "#main-wrapper .gig-page .faq-collapsable"
+    }
+
+    /// Expands each collapsed FAQ entry since collapsed answers have no inner text.
+    async fn get_faq(&self) -> Result<Vec<FaqData>> {
+        let element_selector = Self::faq_selector();
+        log::info!("Find elements: {element_selector}");
+        let faq_els = self.tab.find_elements(element_selector).unwrap_or_default();
+        let mut faq = Vec::with_capacity(faq_els.len());
+        for faq_el in faq_els {
+            log::info!("Find element: {element_selector} .faq-collapsable-title");
+            let Ok(question_el) = faq_el.find_element(".faq-collapsable-title") else {
+                continue;
+            };
+            log::info!("Get inner text: {element_selector} .faq-collapsable-title");
+            let question = question_el.get_inner_text()?.trim().to_owned();
+            // Clicking an expanded entry would collapse it again.
+            log::info!(
+                "Get attribute value: {element_selector} .faq-collapsable-title.aria-expanded"
+            );
+            let expanded = match question_el.get_attribute_value("aria-expanded")? {
+                Some(expanded) => expanded == "true",
+                None => ElementText::find(&faq_el, ".faq-collapsable-content")?
+                    .is_some_and(|answer| !answer.is_empty()),
+            };
+            if !expanded {
+                log::info!("Click: {element_selector} .faq-collapsable-title");
+                question_el.click()?;
+                sleep(Duration::from_secs(BTN_CLICK_WAIT_SECS)).await;
+            }
+            let answer = ElementText::find(&faq_el, ".faq-collapsable-content")?
+                .filter(|answer| !answer.is_empty());
+            faq.push(FaqData { question, answer });
+        }
+        Ok(faq)
+    }
+
+    fn attributes_selector() -> &'static str {
+        "#main-wrapper .gig-page .metadata .metadata-attribute"
+    }
+
+    fn get_attributes(&self) -> Result<Vec<AttributeData>> {
+        let element_selector = Self::attributes_selector();
+        log::info!("Find elements: {element_selector}");
+        let attribute_els = self.tab.find_elements(element_selector).unwrap_or_default();
+        let mut attributes = Vec::with_capacity(attribute_els.len());
+        for attribute_el in attribute_els {
+            let Some(name) = ElementText::find(&attribute_el, "p")? else {
+                continue;
+            };
+            log::info!("Find elements: {element_selector} ul li");
+            let value_els = attribute_el.find_elements("ul li").unwrap_or_default();
+            let mut values = Vec::with_capacity(value_els.len());
+            for value_el in value_els {
+                log::info!("Get inner text: {element_selector} ul li");
+                let value = value_el.get_inner_text()?.trim().to_owned();
+                if !value.is_empty() {
+                    values.push(value);
+                }
+            }
+            attributes.push(AttributeData { name, values });
+        }
+        Ok(attributes)
+    }
+
+    fn reviews_selector() -> &'static str {
+        "#main-wrapper .gig-page .review-list li.review-item-component"
+    }
+
+    fn show_more_reviews_btn_selector() -> &'static str {
+        "#main-wrapper .gig-page .reviews-wrap .load-more-wrapper button"
+    }
+
+    fn get_review<'b>(review_el: &Element<'b>) -> Result<Option<ReviewData>> {
+        let Some(text) = ElementText::find(review_el, ".review-description")? else {
+            return Ok(None);
+        };
+        Ok(Some(ReviewData {
+            reviewer: ElementText::find(review_el, ".username")?,
+            country: ElementText::find(review_el, ".country")?,
+            rating: ElementText::find(review_el, ".rating-score")?
+                .and_then(|rating| rating.parse().ok()),
+            date: ElementText::find(review_el, "time")?,
+            text,
+            price_range: ElementText::find(review_el, ".review-price")?,
+            duration: ElementText::find(review_el, ".review-duration")?,
+            seller_response: ElementText::find(review_el, ".seller-response p")?,
+        }))
+    }
+
+    /// Collects up to `review_limit` reviews, expanding the review list with "show more" as
+    /// needed.
+    async fn get_reviews(&self) -> Result<Vec<ReviewData>> {
+        let element_selector = Self::reviews_selector();
+        let mut previous_count = None;
+        loop {
+            log::info!("Find elements: {element_selector}");
+            let count = self
+                .tab
+                .find_elements(element_selector)
+                .map(|reviews| reviews.len())
+                .unwrap_or(0);
+            // Stop when the limit is reached or "show more" stopped adding reviews.
+            if count >= self.review_limit || previous_count == Some(count) {
+                break;
+            }
+            previous_count = Some(count);
+
+            let btn_selector = Self::show_more_reviews_btn_selector();
+            log::info!("Find element: {btn_selector}");
+            let Ok(show_more_btn) = self.tab.find_element(btn_selector) else {
+                break;
+            };
+            log::info!("Click: {btn_selector}");
+            show_more_btn.click()?;
+            sleep(Duration::from_secs(BTN_CLICK_WAIT_SECS)).await;
+        }
+
+        log::info!("Find elements: {element_selector}");
+        let review_els = self.tab.find_elements(element_selector).unwrap_or_default();
+        let mut reviews = Vec::new();
+        for review_el in review_els.iter().take(self.review_limit) {
+            if let Some(review) = Self::get_review(review_el)? {
+                reviews.push(review);
+            }
+        }
+        Ok(reviews)
+    }
+
     fn current_slide_selector() -> &'static str {
         "#main-wrapper .gig-page .gallery-slideshow .slideshow-slide.current .slide"
     }
@@ -552,6 +2150,10 @@ impl<'a> GigPage<'a> {
         ".gallery-modal .slideshow-slide.current .slide"
     }
 
+    fn gallery_slides_selector() -> &'static str {
+        ".gallery-modal .slideshow-slide"
+    }
+
     fn gallery_modal_selector() -> &'static str {
         ".gallery-modal"
     }
@@ -566,22 +2168,53 @@ impl<'a> GigPage<'a> {
 
     fn get_slide_type<'b>(slide_el: &Element<'b>) -> Result<SlideType> {
         log::info!("Get attribute value: [ref:slide]");
-        let class = slide_el.get_attribute_value("class")?.ok_or(anyhow!(
-            "Element ([ref:slide] does not have the attribute 'class')"
-        ))?;
+        let class =
+            slide_el
+                .get_attribute_value("class")?
+                .ok_or(ScrapeError::AttributeMissing {
+                    selector: "[ref:slide]".to_owned(),
+                    attribute: "class".to_owned(),
+                })?;
         if class.contains("video") {
             Ok(SlideType::Video)
         } else if class.contains("image") {
             Ok(SlideType::Image)
-        } else {
+        } else if class.contains("pdf") || class.contains("document") {
             Ok(SlideType::Pdf)
+        } else if class.contains("audio") {
+            Ok(SlideType::Audio)
+        } else {
+            Ok(SlideType::Unknown(class))
         }
     }
 
+    /// Finds the URL of the document shown by a PDF slide, either as a download link or as the
+    /// source of an embedded viewer.
+    fn get_document_source<'b>(slide_el: &Element<'b>) -> Result<Option<String>> {
+        for (selector, attribute) in [
+            ("a[href]", "href"),
+            ("iframe[src]", "src"),
+            ("embed[src]", "src"),
+            ("object[data]", "data"),
+        ] {
+            log::info!("Find element: [ref:slide] {selector}");
+            if let Ok(source_el) = slide_el.find_element(selector) {
+                log::info!("Get attribute value: [ref:slide] {selector}.{attribute}");
+                if let Some(source) = source_el.get_attribute_value(attribute)? {
+                    return UrlNormalizer::normalize(&source).map(Some);
+                }
+            }
+        }
+        Ok(None)
+    }
+
     async fn switch_to_next_slide(&self) -> Result<()> {
         let element_selector = Self::next_slide_selector();
         log::info!("Find element: {element_selector}");
-        let next_btn = self.tab.find_element(element_selector)?;
+        let next_btn = self
+            .tab
+            .find_element(element_selector)
+            .map_err(ScrapeError::not_found(element_selector, "on the gig page"))?;
         log::info!("Click: {element_selector}");
         next_btn.click()?;
         sleep(Duration::from_secs(BTN_CLICK_WAIT_SECS)).await;
@@ -591,7 +2224,10 @@ impl<'a> GigPage<'a> {
     async fn close_gallery(&self) -> Result<()> {
         let element_selector = Self::gallery_close_btn_selector();
         log::info!("Find element: {element_selector}");
-        let close_btn = self.tab.find_element(element_selector)?;
+        let close_btn = self
+            .tab
+            .find_element(element_selector)
+            .map_err(ScrapeError::not_found(element_selector, "on the gig page"))?;
         log::info!("Click: {element_selector}");
         close_btn.click()?;
         sleep(Duration::from_secs(BTN_CLICK_WAIT_SECS)).await;
@@ -613,111 +2249,395 @@ impl<'a> GigPage<'a> {
         Ok(())
     }
 
-    async fn switch_to_next_gallery_slide(&self) -> Result<()> {
+    /// The index of the slide shown in the gallery modal among all of its slides.
+    fn get_current_gallery_index(&self) -> Result<usize> {
+        let element_selector = Self::gallery_slides_selector();
+        log::info!("Find elements: {element_selector}");
+        let slide_els = self
+            .tab
+            .find_elements(element_selector)
+            .map_err(ScrapeError::not_found(element_selector, "in the gallery"))?;
+        let mut classes = Vec::with_capacity(slide_els.len());
+        for slide_el in &slide_els {
+            log::info!("Get attribute value: [ref:gallery_slide] class");
+            classes.push(slide_el.get_attribute_value("class")?.unwrap_or_default());
+        }
+        Self::current_index(&classes).ok_or_else(|| {
+            ScrapeError::SelectorNotFound {
+                selector: format!("{element_selector}.current"),
+                context: "in the gallery".to_owned(),
+            }
+            .into()
+        })
+    }
+
+    fn current_index(classes: &[String]) -> Option<usize> {
+        classes
+            .iter()
+            .position(|class| class.split_whitespace().any(|class| class == "current"))
+    }
+
+    /// Moves the gallery modal on from the slide at index `from` and returns the index of the
+    /// slide shown then, or `None` when the gallery has no next button or did not advance.
+    async fn switch_to_next_gallery_slide(&self, from: usize) -> Result<Option<usize>> {
         let element_selector = Self::gallery_next_slide_btn_selector();
         log::info!("Find element: {element_selector}");
-        if let Ok(next_btn) = self.tab.find_element(element_selector) {
-            log::info!("Click: {element_selector}");
-            next_btn.click()?;
-            sleep(Duration::from_secs(BTN_CLICK_WAIT_SECS)).await;
+        let Ok(next_btn) = self.tab.find_element(element_selector) else {
+            log::warn!("The gallery has no next button on slide {from}");
+            return Ok(None);
+        };
+        log::info!("Click: {element_selector}");
+        next_btn.click()?;
+        sleep(Duration::from_secs(BTN_CLICK_WAIT_SECS)).await;
+        let index = self.get_current_gallery_index()?;
+        if index == from {
+            log::warn!("The gallery did not advance from slide {from}");
+            return Ok(None);
         }
-        Ok(())
+        Ok(Some(index))
     }
 
-    async fn open_slideshow(&self) -> Result<()> {
-        loop {
+    fn slide_count_selectors() -> [&'static str; 3] {
+        [
+            "#main-wrapper .gig-page .gallery-thumbnails .thumbnail",
+            "#main-wrapper .gig-page .gallery-slideshow .slideshow-dots li",
+            "#main-wrapper .gig-page .gallery-slideshow .slideshow-slide",
+        ]
+    }
+
+    /// Reads the number of gallery slides from the thumbnails, the indicator dots or the slides
+    /// themselves, whichever the gallery has. Galleries of a single slide may show none of them
+    /// besides the current slide.
+    fn get_slide_count(&self) -> usize {
+        for element_selector in Self::slide_count_selectors() {
+            log::info!("Find elements: {element_selector}");
+            if let Ok(elements) = self.tab.find_elements(element_selector)
+                && !elements.is_empty()
+            {
+                return elements.len();
+            }
+        }
+        let element_selector = Self::current_slide_selector();
+        log::info!("Find element: {element_selector}");
+        match self.tab.find_element(element_selector) {
+            Ok(_) => 1,
+            Err(_) => 0,
+        }
+    }
+
+    async fn open_slideshow(&self, slide_count: usize) -> Result<()> {
+        for _ in 0..slide_count {
             let element_selector = Self::gallery_modal_selector();
             log::info!("Find element: {element_selector}");
             let gallery_modal = self.tab.find_element(element_selector);
             if gallery_modal.is_ok() {
-                break Ok(());
+                return Ok(());
             }
             let element_selector = Self::current_slide_selector();
             log::info!("Find element: {element_selector}");
-            let current_slide_el = self.tab.find_element(element_selector)?;
+            let current_slide_el = self
+                .tab
+                .find_element(element_selector)
+                .map_err(ScrapeError::not_found(element_selector, "on the gig page"))?;
             match Self::get_slide_type(&current_slide_el)? {
                 SlideType::Image => {
                     log::info!("Click: {element_selector}");
                     current_slide_el.click()?;
+                    sleep(Duration::from_secs(BTN_CLICK_WAIT_SECS)).await;
                 }
                 _ => {
                     self.switch_to_next_slide().await?;
                 }
             }
         }
+        let element_selector = Self::gallery_modal_selector();
+        log::info!("Find element: {element_selector}");
+        self.tab
+            .find_element(element_selector)
+            .map_err(ScrapeError::not_found(element_selector, "on the gig page"))?;
+        Ok(())
     }
 
-    async fn get_visuals(&self) -> Result<Vec<(String, SlideType)>> {
-        self.open_slideshow().await?;
-        let mut visuals = Vec::new();
-        let visual_exists = |visuals: &mut Vec<(String, SlideType)>, key: &str| {
-            visuals.iter().any(|(url, _)| url == key)
+    fn get_slide_visual(current_slide: &Element<'_>, position: u32) -> Result<Option<VisualData>> {
+        let element_selector = Self::current_gallery_slide_selector();
+        let typ = Self::get_slide_type(current_slide)?;
+        let url = match typ {
+            SlideType::Image => {
+                log::info!("Find element: {element_selector} img");
+                let image_el =
+                    current_slide
+                        .find_element("img")
+                        .map_err(ScrapeError::not_found(
+                            &format!("{element_selector} img"),
+                            "in the gallery",
+                        ))?;
+                log::info!("Get attribute value: {element_selector} img.src");
+                image_el
+                    .get_attribute_value("src")?
+                    .ok_or(ScrapeError::AttributeMissing {
+                        selector: format!("{element_selector} img"),
+                        attribute: "src".to_owned(),
+                    })?
+            }
+            SlideType::Video => {
+                log::info!("Find element: {element_selector} button");
+                let play_btn =
+                    current_slide
+                        .find_element("button")
+                        .map_err(ScrapeError::not_found(
+                            &format!("{element_selector} button"),
+                            "in the gallery",
+                        ))?;
+                log::info!("Click: {element_selector} button");
+                play_btn.click()?;
+                log::info!("Wait for element: {element_selector} video");
+                let video_el =
+                    current_slide
+                        .wait_for_element("video")
+                        .map_err(ScrapeError::not_found(
+                            &format!("{element_selector} video"),
+                            "in the gallery",
+                        ))?;
+                log::info!("Get attribute value: {element_selector} video.src");
+                video_el
+                    .get_attribute_value("src")?
+                    .ok_or(ScrapeError::AttributeMissing {
+                        selector: format!("{element_selector} video"),
+                        attribute: "src".to_owned(),
+                    })?
+            }
+            SlideType::Pdf => match Self::get_document_source(current_slide)? {
+                Some(source) => source,
+                None => {
+                    log::warn!("PDF slide {position} without a document URL");
+                    return Ok(None);
+                }
+            },
+            SlideType::Audio => {
+                log::info!("Skipping audio slide {position}");
+                return Ok(None);
+            }
+            SlideType::Unknown(class) => {
+                log::warn!("Skipping slide {position} of unknown kind (class: '{class}')");
+                return Ok(None);
+            }
         };
+        Ok(Some(VisualData { url, typ, position }))
+    }
+
+    /// Visits each of the `slide_count` slides of the open gallery modal once. The modal opens on
+    /// a later slide when the first ones are not images, so the position of a slide is its index
+    /// in the gallery rather than the order of the visit. The walk ends early when the gallery
+    /// stops advancing or comes back to a visited slide, instead of recording a slide twice.
+    async fn walk_gallery(&self, slide_count: usize) -> Result<Vec<VisualData>> {
+        let mut visuals = Vec::with_capacity(slide_count);
+        let mut visited = HashSet::with_capacity(slide_count);
+        let mut position = self.get_current_gallery_index()?;
         loop {
+            if !visited.insert(position) {
+                log::warn!("The gallery came back to slide {position}");
+                break;
+            }
             let element_selector = Self::current_gallery_slide_selector();
             log::info!("Find element: {element_selector}");
-            let current_slide = self.tab.find_element(element_selector)?;
-            let slide_type = Self::get_slide_type(&current_slide)?;
-            match slide_type {
-                SlideType::Image => {
-                    log::info!("Find element: {element_selector} img");
-                    let image_el = current_slide.find_element("img")?;
-                    log::info!("Get attribute value: {element_selector} img.src");
-                    let source = image_el.get_attribute_value("src")?.ok_or(anyhow!(
-                        "Element ({element_selector} img) has no attribute 'src'."
-                    ))?;
-                    if visual_exists(&mut visuals, &source) {
-                        break;
-                    }
-                    visuals.push((source, SlideType::Image));
-                }
-                SlideType::Video => {
-                    log::info!("Find element: {element_selector} button");
-                    let play_btn = current_slide.find_element("button")?;
-                    log::info!("Click: {element_selector} button");
-                    play_btn.click()?;
-                    log::info!("Wait for element: {element_selector} video");
-                    let video_el = current_slide.wait_for_element("video")?;
-                    log::info!("Get attribute value: {element_selector} video.src");
-                    let source = video_el.get_attribute_value("src")?.ok_or(anyhow!(
-                        "Element ({element_selector} video) has no attribute 'src'."
-                    ))?;
-                    if visual_exists(&mut visuals, &source) {
-                        break;
+            let current_slide = self
+                .tab
+                .find_element(element_selector)
+                .map_err(ScrapeError::not_found(element_selector, "on the gig page"))?;
+            if let Some(visual) = Self::get_slide_visual(&current_slide, position as u32)? {
+                visuals.push(visual);
+            }
+            if visited.len() == slide_count {
+                break;
+            }
+            match self.switch_to_next_gallery_slide(position).await? {
+                Some(next) => position = next,
+                None => break,
+            }
+        }
+        if visited.len() < slide_count {
+            log::warn!("Visited {} of {slide_count} gallery slides", visited.len());
+        }
+        visuals.sort_by_key(|visual| visual.position);
+        Ok(visuals)
+    }
+
+    async fn get_visuals(&self) -> Result<Vec<VisualData>> {
+        let slide_count = self.get_slide_count();
+        log::info!("Gallery slide count: {slide_count}");
+        if slide_count == 0 {
+            log::warn!("Gig has no gallery slides");
+            return Ok(Vec::new());
+        }
+        if slide_count > MAX_GALLERY_SLIDES {
+            log::warn!("Only visiting the first {MAX_GALLERY_SLIDES} of {slide_count} slides");
+        }
+        let slide_count = slide_count.min(MAX_GALLERY_SLIDES);
+
+        let visuals = match self.open_slideshow(slide_count).await {
+            Ok(()) => self.walk_gallery(slide_count).await,
+            Err(e) => Err(e),
+        };
+        // Close the gallery modal even when walking it failed so it does not block the page.
+        if let Err(e) = self.close_gallery().await {
+            match visuals {
+                Ok(_) => return Err(e),
+                Err(_) => log::warn!("Could not close the gallery: {e}"),
+            }
+        }
+        visuals
+    }
+
+    /// Turns the captured media responses into visuals, keeping the largest rendition of each
+    /// asset in the order the assets were first requested.
+    fn get_captured_visuals(captured_media: &CapturedMediaLog) -> Vec<VisualData> {
+        let captured_media = captured_media.lock().unwrap();
+        let mut largest: Vec<&CapturedMedia> = Vec::new();
+        for media in captured_media.iter() {
+            match largest
+                .iter_mut()
+                .find(|largest| largest.asset_name() == media.asset_name())
+            {
+                Some(largest) if media.byte_size > largest.byte_size => *largest = media,
+                Some(_) => (),
+                None => largest.push(media),
+            }
+        }
+        largest
+            .into_iter()
+            .enumerate()
+            .map(|(position, media)| VisualData {
+                url: media.url.clone(),
+                typ: media.slide_type(),
+                position: position as u32,
+            })
+            .collect()
+    }
+
+    async fn get_media_visuals(&self) -> Result<Vec<VisualData>> {
+        match &self.media_source {
+            GigMediaSource::Gallery => self.get_visuals().await,
+            GigMediaSource::Network {
+                captured_media,
+                gallery_fallback,
+            } => {
+                let captured = Self::get_captured_visuals(captured_media);
+                let slide_count = self.get_slide_count().min(MAX_GALLERY_SLIDES);
+                log::info!(
+                    "Captured {} gig media responses for {slide_count} slides",
+                    captured.len()
+                );
+                if captured.len() >= slide_count || !*gallery_fallback {
+                    return Ok(captured);
+                }
+                log::info!("Gig media missing from the capture, walking the gallery as well");
+                let mut visuals = self.get_visuals().await?;
+                // Keep captured media the gallery walk did not find.
+                for visual in captured {
+                    let asset_name = CapturedMedia::asset_name_of(&visual.url);
+                    if !visuals
+                        .iter()
+                        .any(|found| CapturedMedia::asset_name_of(&found.url) == asset_name)
+                    {
+                        visuals.push(VisualData {
+                            position: visuals.len() as u32,
+                            ..visual
+                        });
                     }
-                    visuals.push((source, SlideType::Video));
                 }
-                _ => (),
+                Ok(visuals)
             }
-            self.switch_to_next_gallery_slide().await?;
         }
-        self.close_gallery().await?;
-        Ok(visuals)
     }
 
-    async fn scrape(&self) -> Result<GigData> {
+    async fn scrape(self) -> Result<GigData> {
         let url = self.get_url()?;
+        if url != self.card.url {
+            log::info!("Gig {} redirected to {url}", self.card.url);
+        }
         let description = self.get_about()?;
         let title = self.get_title()?;
+        let seller = self.get_seller()?;
         ModalCloser::close_open_modal(self.tab).await?;
         self.close_education_box().await?;
-        let visuals = self
-            .get_visuals()
-            .await?
-            .into_iter()
-            .map(|visual| VisualData {
-                url: visual.0,
-                typ: visual.1,
-            })
-            .collect();
+        let packages = self.get_packages().await?;
+        let reviews = self.get_reviews().await?;
+        let tags = self.get_tags()?;
+        let faq = self.get_faq().await?;
+        let attributes = self.get_attributes()?;
+        // The rest of the gig is worth saving without its gallery.
+        let (visuals, gallery_error) = match self.get_media_visuals().await {
+            Ok(visuals) => (visuals, None),
+            Err(e) if error::is_connection_lost(&e) => return Err(e),
+            Err(e) => {
+                log::error!("Could not collect the gallery of {url}: {e}");
+                (Vec::new(), Some(format!("{e:#}")))
+            }
+        };
         Ok(GigData {
             url,
             title,
             description,
             visuals,
-            page: self.page,
+            packages,
+            seller,
+            reviews,
+            tags,
+            faq,
+            attributes,
+            gallery_error,
+            target: self.target,
+            card: self.card,
+        })
+    }
+}
+
+struct ScrapeTargetCursor {
+    targets: Vec<ScrapeTarget>,
+    exhausted: Vec<bool>,
+    rotation: TargetRotation,
+    idx: usize,
+}
+
+impl ScrapeTargetCursor {
+    fn new(targets: Vec<ScrapeTarget>, rotation: TargetRotation) -> Result<Self> {
+        if targets.is_empty() {
+            return Err(anyhow!("No scrape targets configured"));
+        }
+        if let Some(target) = targets.iter().find(|target| !target.is_valid()) {
+            return Err(anyhow!(
+                "Scrape target {target:?} needs either a search query or a category and menu"
+            ));
+        }
+        Ok(Self {
+            exhausted: vec![false; targets.len()],
+            targets,
+            rotation,
+            idx: 0,
         })
     }
+
+    /// Returns the target to scrape next, skipping exhausted ones, or `None` once all targets are
+    /// exhausted.
+    fn current(&mut self) -> Option<&ScrapeTarget> {
+        let len = self.targets.len();
+        let idx = (0..len)
+            .map(|offset| (self.idx + offset) % len)
+            .find(|idx| !self.exhausted[*idx])?;
+        self.idx = idx;
+        Some(&self.targets[idx])
+    }
+
+    fn mark_exhausted(&mut self) {
+        self.exhausted[self.idx] = true;
+        self.idx = (self.idx + 1) % self.targets.len();
+    }
+
+    fn advance(&mut self) {
+        if self.rotation == TargetRotation::RoundRobin {
+            self.idx = (self.idx + 1) % self.targets.len();
+        }
+    }
 }
 
 struct ModalCloser {}
@@ -774,6 +2694,23 @@ impl ErrorPageDetector {
         Ok(false)
     }
 
+    /// Reloads the tab until it no longer shows an error page, giving up after
+    /// `MAX_ERROR_PAGE_RELOADS` reloads since the scraper is most likely being blocked.
+    async fn wait_until_cleared(tab: &Arc<Tab>) -> Result<()> {
+        let mut reloads = 0;
+        while Self::process(tab).await? {
+            reloads += 1;
+            if reloads >= MAX_ERROR_PAGE_RELOADS && Self::is_error_page(tab)? {
+                return Err(ScrapeError::BlockedByBotProtection {
+                    url: tab.get_url(),
+                    reloads,
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
     async fn process(tab: &Arc<Tab>) -> Result<bool> {
         let element_selector = Self::error_code_selector();
         log::info!("Find element: {element_selector}");
@@ -787,6 +2724,112 @@ impl ErrorPageDetector {
     }
 }
 
+struct NavigationWaiter {}
+
+impl NavigationWaiter {
+    /// Waits until the tab has navigated to `page`, which names the page for the error.
+    fn wait(tab: &Tab, page: &str) -> Result<()> {
+        tab.wait_until_navigated()
+            .map_err(|e| ScrapeError::NavigationTimeout {
+                page: page.to_owned(),
+                message: e.to_string(),
+            })?;
+        Ok(())
+    }
+}
+
+struct ElementText {}
+
+impl ElementText {
+    /// Returns the trimmed inner text of the first descendant matching `selector`, or `None` when
+    /// there is no such element.
+    fn find<'b>(parent: &Element<'b>, selector: &str) -> Result<Option<String>> {
+        log::info!("Find element: [ref:parent] {selector}");
+        match parent.find_element(selector) {
+            Ok(element) => {
+                log::info!("Get inner text: [ref:parent] {selector}");
+                Ok(Some(element.get_inner_text()?.trim().to_owned()))
+            }
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Price {
+    amount: f64,
+    /// The ISO code of the currency, or the symbol as displayed when it is not a known one;
+    /// `None` when the price has no symbol.
+    currency: Option<String>,
+}
+
+struct PriceParser {}
+
+impl PriceParser {
+    /// Parses prices as displayed by Fiverr, e.g. "US$45", "€1,200" or "From US$30".
+    fn parse(text: &str) -> Option<Price> {
+        let amount_start = text.find(|c: char| c.is_ascii_digit())?;
+        let amount_end = text[amount_start..]
+            .find(|c: char| !(c.is_ascii_digit() || c == ',' || c == '.'))
+            .map_or(text.len(), |end| amount_start + end);
+        let amount = text[amount_start..amount_end]
+            .replace(',', "")
+            .trim_end_matches('.')
+            .parse()
+            .ok()?;
+        let symbol = text[..amount_start]
+            .split_whitespace()
+            .last()
+            .unwrap_or_default();
+        Some(Price {
+            amount,
+            currency: Self::currency_code(symbol).map(str::to_owned),
+        })
+    }
+
+    fn currency_code(symbol: &str) -> Option<&str> {
+        match symbol {
+            "" => None,
+            "$" | "US$" => Some("USD"),
+            "€" => Some("EUR"),
+            "£" => Some("GBP"),
+            "₹" => Some("INR"),
+            "CA$" => Some("CAD"),
+            "A$" => Some("AUD"),
+            symbol => Some(symbol),
+        }
+    }
+}
+
+struct ReviewCountParser {}
+
+impl ReviewCountParser {
+    /// Parses review counts as displayed on gig cards, e.g. "(532)", "(1k+)" or "(1.2k)".
+    fn parse(text: &str) -> Option<u32> {
+        let count = text.replace(['(', ')', '+', ','], "").trim().to_lowercase();
+        let (count, multiplier) = match count.strip_suffix('k') {
+            Some(count) => (count, 1_000.0),
+            None => match count.strip_suffix('m') {
+                Some(count) => (count, 1_000_000.0),
+                None => (count.as_str(), 1.0),
+            },
+        };
+        let count: f64 = count.trim().parse().ok()?;
+        Some((count * multiplier).round() as u32)
+    }
+}
+
+struct NumberParser {}
+
+impl NumberParser {
+    fn first_u32(text: &str) -> Option<u32> {
+        text.split(|c: char| !c.is_ascii_digit())
+            .find(|digits| !digits.is_empty())?
+            .parse()
+            .ok()
+    }
+}
+
 struct QueryPathStripper {}
 
 impl QueryPathStripper {
@@ -811,138 +2854,1809 @@ impl UrlNormalizer {
 }
 
 struct ResourceDownloader {
-    download_dir: PathBuf,
+    /// Holds the files being downloaded until they are put into the media store.
+    staging_dir: PathBuf,
+    media_store: Arc<dyn MediaStore>,
+    video_processor: Option<VideoProcessor>,
+    client: reqwest::Client,
+    concurrency: usize,
+    max_file_size: Option<u64>,
+    max_attempts: u32,
+    retry_delay: Duration,
 }
 
 impl ResourceDownloader {
-    async fn new(download_dir: &str) -> Result<Self> {
-        let download_dir = Path::new(download_dir).to_path_buf();
-        fs::create_dir_all(&download_dir).await?;
-        Ok(Self { download_dir })
+    async fn new(app_config: &AppConfig) -> Result<Self> {
+        let config = &app_config.media_download;
+        let download_dir = Path::new(&app_config.download_dir);
+        let staging_dir = download_dir.join(media_store::STAGING_DIR);
+        fs::create_dir_all(&staging_dir).await?;
+        let media_store = media_store::from_config(&app_config.media_store, download_dir).await?;
+        let video_processor = VideoProcessor::from_config(&app_config.video_processing).await;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()?;
+        Ok(Self {
+            staging_dir,
+            media_store,
+            video_processor,
+            client,
+            concurrency: config.concurrency.max(1),
+            max_file_size: config.max_file_size_mb.map(|mb| mb * 1024 * 1024),
+            max_attempts: config.max_attempts.max(1),
+            retry_delay: Duration::from_millis(config.retry_delay_ms),
+        })
     }
 
-    pub async fn download_media_files(&self, visuals: Vec<VisualData>) -> Result<Vec<VisualData>> {
-        let client = reqwest::Client::new();
-        let mut results = Vec::new();
-
-        for visual in visuals {
-            let file_path = match self.download_single_file(&client, &visual.url).await {
-                Ok(file_path) => file_path,
-                Err(e) => {
-                    log::error!("Error downloading visual: {}", visual.url);
-                    log::error!("{e}");
-                    continue;
+    /// Downloads the visuals with at most `concurrency` downloads in flight, returning the
+    /// outcomes in the order of the visuals.
+    pub async fn download_media_files(&self, visuals: Vec<VisualData>) -> Vec<DownloadedVisual> {
+        stream::iter(visuals)
+            .map(|visual| async move {
+                let outcome = self.download(&visual.url).await;
+                if let Ok(file) = &outcome {
+                    Self::check_slide_type(&visual, file);
                 }
+                DownloadedVisual { visual, outcome }
+            })
+            .buffered(self.concurrency)
+            .collect()
+            .await
+    }
+
+    /// Downloads the file, retrying transient failures with exponential backoff.
+    async fn download(&self, uri: &str) -> Result<DownloadedFile, DownloadFailure> {
+        let mut attempt = 1;
+        loop {
+            let error = match self.download_single_file(uri).await {
+                Ok(file) => return Ok(file),
+                Err(error) => error,
             };
-            let file_path_str = file_path
-                .to_str()
-                .ok_or(anyhow!("Encountered path without string"))?
-                .to_owned();
-            results.push(VisualData {
-                url: file_path_str,
-                typ: visual.typ,
-            });
+            let reqwest_error = error.downcast_ref::<reqwest::Error>();
+            let http_status = reqwest_error
+                .and_then(|e| e.status())
+                .map(|status| status.as_u16());
+            let transient = reqwest_error.is_some_and(Self::is_transient);
+
+            if !transient || attempt >= self.max_attempts {
+                log::error!("Error downloading visual: {uri}");
+                log::error!("{error}");
+                return Err(DownloadFailure {
+                    error: error.to_string(),
+                    http_status,
+                });
+            }
+
+            let delay = self.retry_delay_after(attempt);
+            log::warn!(
+                "Attempt {attempt} of downloading {uri} failed, retrying in {delay:?}: {error}"
+            );
+            sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// The wait after the failed `attempt`, doubled for every attempt up to
+    /// `MAX_DOWNLOAD_RETRY_DELAY_SECS`.
+    fn retry_delay_after(&self, attempt: u32) -> Duration {
+        self.retry_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(Duration::from_secs(MAX_DOWNLOAD_RETRY_DELAY_SECS))
+    }
+
+    fn is_transient(error: &reqwest::Error) -> bool {
+        match error.status() {
+            Some(status) => {
+                status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+            None => {
+                error.is_timeout() || error.is_connect() || error.is_request() || error.is_body()
+            }
         }
+    }
 
-        Ok(results)
+    /// Warns when the downloaded file is not what the gallery slide showed. Slides of an unknown
+    /// kind are never downloaded.
+    fn check_slide_type(visual: &VisualData, file: &DownloadedFile) {
+        let Some(downloaded_type) = file
+            .mime_type
+            .as_deref()
+            .and_then(SlideType::from_mime_type)
+        else {
+            return;
+        };
+        if visual.typ != downloaded_type {
+            log::warn!(
+                "Slide shown as {} downloaded as {}: {}",
+                visual.typ,
+                file.mime_type.as_deref().unwrap_or_default(),
+                visual.url
+            );
+        }
     }
 
-    async fn download_single_file(&self, client: &reqwest::Client, uri: &str) -> Result<PathBuf> {
+    async fn download_single_file(&self, uri: &str) -> Result<DownloadedFile> {
         // Parse and validate URL
         let url = Url::parse(uri)?;
 
-        // Extract file extension from URL path
-        let path = url.path();
-        let extension = Path::new(path)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or(".mp4")
-            .to_lowercase();
-
-        // Generate UUID for new filename
+        // Download under a UUID name; the final name is known once the file is hashed
         let uuid = Uuid::new_v4();
-        let uuid_filename = format!("{}.{}", uuid, extension);
+        let part_path = self.staging_dir.join(format!("{uuid}.part"));
 
         // Download the file
-        let response = client.get(uri).send().await?;
+        let response = self.client.get(uri).send().await?;
 
         if !response.status().is_success() {
             return Err(response.error_for_status().unwrap_err().into());
         }
 
-        let bytes = response.bytes().await?;
+        let content_length = response.content_length();
+        if let (Some(content_length), Some(max_file_size)) = (content_length, self.max_file_size)
+            && content_length > max_file_size
+        {
+            return Err(ScrapeError::DownloadError {
+                url: uri.to_owned(),
+                message: format!(
+                    "File of {content_length} bytes exceeds the maximum of {max_file_size} bytes"
+                ),
+            }
+            .into());
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .map(str::to_owned);
+
+        log::info!("Downloading: {uri}");
+        let streamed = self
+            .stream_to_file(response, &part_path, uri, content_length)
+            .await;
+        let (byte_size, sha256, head) = match streamed {
+            Ok(streamed) => streamed,
+            Err(e) => {
+                let _ = fs::remove_file(&part_path).await;
+                return Err(e);
+            }
+        };
+
+        let media_type = media_type::detect(content_type.as_deref(), &head, url.path());
+        let video = match (&self.video_processor, media_type.mime_type.as_deref()) {
+            (Some(video_processor), Some(mime_type)) if mime_type.starts_with("video/") => Some(
+                self.process_video(video_processor, &part_path, &sha256)
+                    .await,
+            ),
+            _ => None,
+        };
+        let image_hashes = match media_type.mime_type.as_deref() {
+            Some(mime_type) if mime_type.starts_with("image/") && mime_type != "image/svg+xml" => {
+                let hashes = match fs::read(&part_path).await {
+                    Ok(bytes) => Self::hash_image(bytes).await,
+                    Err(e) => Err(e.into()),
+                };
+                match hashes {
+                    Ok(hashes) => Some(hashes),
+                    Err(e) => {
+                        log::warn!("Error hashing image {uri}: {e}");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        let key = Self::content_key(&sha256, &media_type.extension);
+        let stored = match self.media_store.put(&part_path, &key).await {
+            Ok(stored) => stored,
+            Err(e) => {
+                let _ = fs::remove_file(&part_path).await;
+                return Err(e);
+            }
+        };
+        match stored.existed {
+            true => log::info!("Already stored as {}: {uri}", stored.uri),
+            false => log::info!("Downloaded {byte_size} bytes to {}: {uri}", stored.uri),
+        }
+
+        Ok(DownloadedFile {
+            uri: stored.uri,
+            mime_type: media_type.mime_type,
+            byte_size,
+            sha256,
+            video,
+            image_hashes,
+        })
+    }
+
+    /// Computes the perceptual hashes of an image off the async runtime.
+    async fn hash_image(bytes: Vec<u8>) -> Result<ImageHashes> {
+        tokio::task::spawn_blocking(move || ImageHashes::compute(&bytes)).await?
+    }
+
+    async fn hash_stored_image(&self, uri: &str) -> Result<ImageHashes> {
+        Self::hash_image(self.media_store.read(uri).await?).await
+    }
+
+    /// Probes the downloaded video and stores its poster frame next to it. Failures are logged,
+    /// leaving the metadata that could not be determined unset.
+    async fn process_video(
+        &self,
+        video_processor: &VideoProcessor,
+        video: &Path,
+        sha256: &str,
+    ) -> ProcessedVideo {
+        let metadata = video_processor.probe(video).await.unwrap_or_else(|e| {
+            log::warn!("Error probing video {}: {e}", video.display());
+            VideoMetadata::default()
+        });
+
+        let poster = video.with_extension("poster.jpg");
+        let poster_uri = match video_processor
+            .extract_poster(video, &metadata, &poster)
+            .await
+        {
+            Ok(()) => {
+                let key = Self::content_key(sha256, "poster.jpg");
+                match self.media_store.put(&poster, &key).await {
+                    Ok(stored) => Some(stored.uri),
+                    Err(e) => {
+                        log::warn!("Error storing poster frame of {}: {e}", video.display());
+                        None
+                    }
+                }
+            }
+            Err(e) => {
+                log::warn!("Error extracting poster frame of {}: {e}", video.display());
+                None
+            }
+        };
+        if poster_uri.is_none() {
+            let _ = fs::remove_file(&poster).await;
+        }
+
+        ProcessedVideo {
+            metadata,
+            poster_uri,
+        }
+    }
+
+    /// The key a file is stored under: `ab/cd/<sha256>.<ext>`, where `ab` and `cd` are the first
+    /// two bytes of the hash. Identical files share a single key.
+    fn content_key(sha256: &str, extension: &str) -> String {
+        format!("{}/{}/{sha256}.{extension}", &sha256[..2], &sha256[2..4])
+    }
+
+    /// Deletes the stored files that are not in `referenced` and returns how many files were (or
+    /// with `dry_run` would be) deleted. Refuses to delete anything when none of the referenced
+    /// files is in the store, which points at a misconfigured store rather than garbage.
+    async fn collect_garbage(&self, referenced: &HashSet<String>, dry_run: bool) -> Result<u64> {
+        let mut canonical_referenced = HashSet::with_capacity(referenced.len());
+        for uri in referenced {
+            canonical_referenced.insert(self.media_store.canonical_uri(uri).await?);
+        }
+        let stored = self.media_store.list().await?;
+        if !canonical_referenced.is_empty()
+            && !stored.is_empty()
+            && !stored.iter().any(|uri| canonical_referenced.contains(uri))
+        {
+            return Err(anyhow!(
+                "None of the {} referenced media files is in the media store, check download_dir and media_store",
+                canonical_referenced.len()
+            ));
+        }
+
+        let mut deleted = 0;
+        for uri in stored {
+            if canonical_referenced.contains(&uri) {
+                continue;
+            }
+            if dry_run {
+                log::info!("Would delete: {uri}");
+            } else {
+                log::info!("Delete: {uri}");
+                self.media_store.delete(&uri).await?;
+            }
+            deleted += 1;
+        }
+        Ok(deleted)
+    }
 
-        // Write to file
-        let file_path = self.download_dir.join(&uuid_filename);
-        let mut file = fs::File::create(&file_path).await?;
-        file.write_all(&bytes).await?;
+    /// Writes the response body to `part_path` chunk by chunk, returning its size, its sha256 and
+    /// its leading bytes for sniffing the file type.
+    async fn stream_to_file(
+        &self,
+        mut response: reqwest::Response,
+        part_path: &Path,
+        uri: &str,
+        content_length: Option<u64>,
+    ) -> Result<(u64, String, Vec<u8>)> {
+        let mut file = fs::File::create(part_path).await?;
+        let mut hasher = Sha256::new();
+        let mut head = Vec::new();
+        let mut byte_size = 0;
+        let mut next_progress = DOWNLOAD_PROGRESS_STEP_BYTES;
+
+        while let Some(chunk) = response.chunk().await? {
+            byte_size += chunk.len() as u64;
+            if let Some(max_file_size) = self.max_file_size
+                && byte_size > max_file_size
+            {
+                return Err(ScrapeError::DownloadError {
+                    url: uri.to_owned(),
+                    message: format!("File exceeds the maximum of {max_file_size} bytes"),
+                }
+                .into());
+            }
+            hasher.update(&chunk);
+            file.write_all(&chunk).await?;
+            if head.len() < media_type::SNIFF_LEN {
+                let missing = media_type::SNIFF_LEN - head.len();
+                head.extend_from_slice(&chunk[..missing.min(chunk.len())]);
+            }
+
+            if byte_size >= next_progress {
+                match content_length {
+                    Some(content_length) => {
+                        log::info!("Downloaded {byte_size}/{content_length} bytes: {uri}")
+                    }
+                    None => log::info!("Downloaded {byte_size} bytes: {uri}"),
+                }
+                next_progress = byte_size + DOWNLOAD_PROGRESS_STEP_BYTES;
+            }
+        }
         file.flush().await?;
 
-        Ok(file_path)
+        Ok((byte_size, hex::encode(hasher.finalize()), head))
     }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
     let app_config: AppConfig = Figment::new()
         .merge(Yaml::file("app-config.yaml"))
         .extract()?;
 
-    Logger::try_with_str(app_config.log_level)?.start()?;
+    Logger::try_with_str(&app_config.log_level)?.start()?;
 
     let connection_options = SqliteConnectOptions::from_str(&app_config.database_url)
         .unwrap()
         .create_if_missing(true);
 
     let db_pool = SqlitePool::connect_with(connection_options).await?;
+
+    match cli.command {
+        Some(Command::Migrate { action }) => match action {
+            MigrateCommand::Status => migrate::status(&db_pool).await,
+            MigrateCommand::Up => migrate::up(&db_pool).await,
+        },
+        Some(Command::Gigs { action }) => {
+            migrate::up(&db_pool).await?;
+            run_gigs_command(action, ScrapedGigsStore::new(db_pool)).await
+        }
+        Some(Command::Media { action }) => {
+            migrate::up(&db_pool).await?;
+            run_media_command(action, &app_config, ScrapedGigsStore::new(db_pool)).await
+        }
+        Some(Command::Visuals { action }) => {
+            migrate::up(&db_pool).await?;
+            run_visuals_command(action, &app_config, ScrapedGigsStore::new(db_pool)).await
+        }
+        None => {
+            migrate::up(&db_pool).await?;
+            run_scraper(app_config, db_pool).await
+        }
+    }
+}
+
+async fn run_gigs_command(action: GigsCommand, gigs_store: ScrapedGigsStore) -> Result<()> {
+    match action {
+        GigsCommand::MarkIncomplete { url } => {
+            let url = UrlNormalizer::normalize(QueryPathStripper::strip(&url))?;
+            if !gigs_store.mark_incomplete(&url).await? {
+                return Err(anyhow!("No scraped gig with URL '{url}'"));
+            }
+            log::info!("Marked gig as incomplete: {url}");
+        }
+        GigsCommand::NormalizeDescriptions => {
+            let updated = gigs_store.normalize_descriptions().await?;
+            log::info!("Normalized the descriptions of {updated} gigs");
+        }
+        GigsCommand::Failed => {
+            for gig in gigs_store.failed_gigs().await? {
+                let status = match gig.quarantined {
+                    true => "quarantined",
+                    false => "failing",
+                };
+                println!(
+                    "{status:<11}  {:>2} attempts  {}  {}",
+                    gig.attempts, gig.last_failed_at, gig.url
+                );
+                if let Some(selector) = gig.selector {
+                    println!("    selector: {selector}");
+                }
+                println!("    error: {}", gig.last_error);
+            }
+        }
+        // The URL is required unless --all is given, which conflicts with it.
+        GigsCommand::Requeue { url, .. } => {
+            let url = url
+                .map(|url| UrlNormalizer::normalize(QueryPathStripper::strip(&url)))
+                .transpose()?;
+            let requeued = gigs_store.requeue_failed_gigs(url.as_deref()).await?;
+            if url.is_some() && requeued == 0 {
+                return Err(anyhow!(
+                    "No failed gig with URL '{}'",
+                    url.unwrap_or_default()
+                ));
+            }
+            log::info!("Requeued {requeued} failed gigs");
+        }
+    }
+    Ok(())
+}
+
+async fn run_media_command(
+    action: MediaCommand,
+    app_config: &AppConfig,
+    gigs_store: ScrapedGigsStore,
+) -> Result<()> {
+    let resource_downloader = ResourceDownloader::new(app_config).await?;
+    match action {
+        MediaCommand::Gc { dry_run } => {
+            let referenced = gigs_store.referenced_media_files().await?;
+            let deleted = resource_downloader
+                .collect_garbage(&referenced, dry_run)
+                .await?;
+            match dry_run {
+                true => log::info!("{deleted} unreferenced media files would be deleted"),
+                false => log::info!("Deleted {deleted} unreferenced media files"),
+            }
+        }
+        MediaCommand::RetryFailed => {
+            let failed_visuals = gigs_store.failed_visuals().await?;
+            log::info!("Retrying {} failed downloads", failed_visuals.len());
+            let resource_downloader = &resource_downloader;
+            let outcomes: Vec<_> = stream::iter(failed_visuals)
+                .map(|visual| async move {
+                    let outcome = resource_downloader.download(&visual.source_url).await;
+                    (visual.id, outcome)
+                })
+                .buffered(resource_downloader.concurrency)
+                .collect()
+                .await;
+
+            let mut downloaded = 0;
+            for (visual_id, outcome) in outcomes {
+                downloaded += outcome.is_ok() as usize;
+                gigs_store
+                    .update_visual_download(&visual_id, outcome)
+                    .await?;
+            }
+            log::info!("Downloaded {downloaded} previously failed visuals");
+        }
+    }
+    Ok(())
+}
+
+async fn run_visuals_command(
+    action: VisualsCommand,
+    app_config: &AppConfig,
+    gigs_store: ScrapedGigsStore,
+) -> Result<()> {
+    match action {
+        VisualsCommand::Hash => {
+            let resource_downloader = ResourceDownloader::new(app_config).await?;
+            let images = gigs_store.unhashed_images().await?;
+            log::info!("Hashing {} images", images.len());
+            let mut hashed = 0;
+            for (visual_id, storage_uri) in images {
+                match resource_downloader.hash_stored_image(&storage_uri).await {
+                    Ok(hashes) => {
+                        gigs_store.save_image_hashes(&visual_id, hashes).await?;
+                        hashed += 1;
+                    }
+                    Err(e) => log::warn!("Error hashing image {storage_uri}: {e}"),
+                }
+            }
+            log::info!("Hashed {hashed} images");
+        }
+        VisualsCommand::Similar { id, max_distance } => {
+            let images = gigs_store.hashed_images().await?;
+            let image = images
+                .iter()
+                .find(|image| image.id == id)
+                .ok_or(anyhow!("No hashed image with visual ID '{id}'"))?;
+            let mut similar: Vec<_> = images
+                .iter()
+                .filter(|other| other.id != image.id)
+                .map(|other| {
+                    let distances = (
+                        image_hash::distance(image.hashes.phash, other.hashes.phash),
+                        image_hash::distance(image.hashes.dhash, other.hashes.dhash),
+                        image_hash::distance(image.hashes.ahash, other.hashes.ahash),
+                    );
+                    (distances, other)
+                })
+                .filter(|((phash_distance, _, _), _)| *phash_distance <= max_distance)
+                .collect();
+            similar.sort_by_key(|(distances, _)| *distances);
+
+            println!("pHash  dHash  aHash  visual  gig");
+            for ((phash, dhash, ahash), other) in similar {
+                println!(
+                    "{phash:>5}  {dhash:>5}  {ahash:>5}  {}  {}",
+                    other.id, other.gig_url
+                );
+            }
+        }
+        VisualsCommand::Clusters { max_distance } => {
+            let images = gigs_store.hashed_images().await?;
+            let phashes: Vec<u64> = images.iter().map(|image| image.hashes.phash).collect();
+            let mut clusters: Vec<(usize, Vec<&HashedImage>)> =
+                image_hash::clusters(&phashes, max_distance)
+                    .into_iter()
+                    .map(|cluster| {
+                        let images: Vec<_> = cluster.into_iter().map(|i| &images[i]).collect();
+                        let gigs: HashSet<_> = images.iter().map(|image| &image.gig_id).collect();
+                        (gigs.len(), images)
+                    })
+                    .filter(|(gig_count, _)| *gig_count > 1)
+                    .collect();
+            clusters.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+            for (number, (gig_count, images)) in clusters.iter().enumerate() {
+                println!(
+                    "Cluster {}: {} images across {gig_count} gigs",
+                    number + 1,
+                    images.len()
+                );
+                for image in images {
+                    println!(
+                        "  {}  {}  {}",
+                        image.id,
+                        image.gig_url,
+                        image.storage_uri.as_deref().unwrap_or_default()
+                    );
+                }
+            }
+            log::info!("Found {} clusters", clusters.len());
+        }
+    }
+    Ok(())
+}
+
+/// The seconds to wait before starting a new session after `consecutive_failures` sessions failed
+/// in a row, or `None` once that many failures exhaust the supervisor.
+fn restart_backoff(config: &SupervisorConfig, consecutive_failures: u32) -> Option<u64> {
+    if consecutive_failures >= config.max_consecutive_failures {
+        return None;
+    }
+    let backoff = config
+        .initial_backoff_secs
+        .saturating_mul(2u64.saturating_pow(consecutive_failures.saturating_sub(1)))
+        .min(config.max_backoff_secs);
+    Some(backoff)
+}
+
+/// Decides how the main loop goes on after a step failed that no gig is to blame for. The step
+/// is retried `MAX_STEP_RETRIES` times in a row and then left to the supervisor, unless the
+/// listing ran out of pages.
+fn next_session_action(error: &anyhow::Error, retries: &mut u32) -> ErrorAction {
+    match error::action_for(error) {
+        ErrorAction::Retry | ErrorAction::SkipGig if *retries < MAX_STEP_RETRIES => {
+            *retries += 1;
+            log::warn!("Retrying ({}/{MAX_STEP_RETRIES}): {error}", *retries);
+            ErrorAction::Retry
+        }
+        ErrorAction::SwitchTarget => {
+            *retries = 0;
+            ErrorAction::SwitchTarget
+        }
+        _ => {
+            *retries = 0;
+            ErrorAction::Stop
+        }
+    }
+}
+
+/// Decides how the main loop goes on after a failed step, passing over the step instead of
+/// retrying it once it has been retried `MAX_STEP_RETRIES` times in a row.
+fn next_action(error: &anyhow::Error, retries: &mut u32) -> ErrorAction {
+    match error::action_for(error) {
+        ErrorAction::Retry if *retries < MAX_STEP_RETRIES => {
+            *retries += 1;
+            log::warn!("Retrying ({}/{MAX_STEP_RETRIES}): {error}", *retries);
+            ErrorAction::Retry
+        }
+        ErrorAction::Retry => {
+            *retries = 0;
+            ErrorAction::SkipGig
+        }
+        action => {
+            *retries = 0;
+            action
+        }
+    }
+}
+
+/// Whether a failure of scraping a gig counts towards quarantining it, once the main loop decided
+/// on `action` for it. Transient failures only count when they outlast their retries.
+fn counts_against_gig(error: &anyhow::Error, action: ErrorAction) -> bool {
+    match action {
+        ErrorAction::Retry => false,
+        _ => error::is_caused_by_gig(error) || error::action_for(error) == ErrorAction::Retry,
+    }
+}
+
+async fn run_scraper(app_config: AppConfig, db_pool: SqlitePool) -> Result<()> {
     let gigs_store = Arc::new(ScrapedGigsStore::new(db_pool));
 
-    let resource_downloader = ResourceDownloader::new(&app_config.download_dir).await?;
+    let resource_downloader = ResourceDownloader::new(&app_config).await?;
+
+    let target_cursor =
+        ScrapeTargetCursor::new(app_config.scrape_targets, app_config.target_rotation)?;
+    let gig_filter = Arc::new(GigFilter::new(app_config.gig_filter)?);
+
+    let mut scraper = Scraper {
+        gigs_store,
+        resource_downloader,
+        target_cursor,
+        gig_filter,
+        browser_ws_url: app_config.browser_ws_url,
+        review_limit: app_config.review_limit,
+        media_capture: app_config.media_capture,
+        quarantine_after: app_config.quarantine_after,
+        consecutive_failures: 0,
+        listed_cards: HashSet::new(),
+    };
+    scraper.supervise(&app_config.supervisor).await
+}
+
+/// The state of the main loop, kept across the browser sessions of the supervisor.
+struct Scraper {
+    gigs_store: Arc<ScrapedGigsStore>,
+    resource_downloader: ResourceDownloader,
+    target_cursor: ScrapeTargetCursor,
+    gig_filter: Arc<GigFilter>,
+    browser_ws_url: String,
+    review_limit: usize,
+    media_capture: MediaCapture,
+    quarantine_after: u32,
+    /// The sessions that failed since a gig was last scraped.
+    consecutive_failures: u32,
+    /// The target, page and position of the listing cards recorded in this run, so that walking
+    /// a page again after each gig, retry or new session records its cards only once.
+    listed_cards: HashSet<(String, u32, u32)>,
+}
+
+impl Scraper {
+    /// Runs browser sessions until all targets are exhausted. A failed session is followed by a
+    /// new one with a fresh browser connection after an exponentially growing backoff, unless the
+    /// error cannot be fixed by restarting or too many sessions failed in a row.
+    async fn supervise(&mut self, config: &SupervisorConfig) -> Result<()> {
+        loop {
+            let Err(e) = self.run_session().await else {
+                return Ok(());
+            };
+
+            self.consecutive_failures += 1;
+            if !error::is_restartable(&e) {
+                log::error!("Scraper failed, not restarting: {e}");
+                return Err(e);
+            }
+            let Some(backoff) = restart_backoff(config, self.consecutive_failures) else {
+                log::error!(
+                    "Scraper failed {} times in a row, giving up",
+                    self.consecutive_failures
+                );
+                return Err(e);
+            };
+
+            match error::is_connection_lost(&e) {
+                true => log::error!("Browser connection lost: {e}"),
+                false => log::error!("Scraper failed: {e}"),
+            }
+            log::info!(
+                "Restarting with a new browser connection in {backoff}s ({}/{} consecutive failures)",
+                self.consecutive_failures,
+                config.max_consecutive_failures
+            );
+            sleep(Duration::from_secs(backoff)).await;
+        }
+    }
+
+    /// Connects to the browser and scrapes gigs until all targets are exhausted or a step fails
+    /// in a way the loop cannot handle itself.
+    async fn run_session(&mut self) -> Result<()> {
+        let browser = CustomBrowser::new(self.browser_ws_url.clone(), Duration::from_secs(600))?;
+
+        let mut fiverr_tab = browser.get_fiverr_tab()?;
+        log::info!("Fiverr tab title: {}", fiverr_tab.get_title()?);
 
-    let browser = CustomBrowser::new(app_config.browser_ws_url, Duration::from_secs(600))?;
+        ModalCloser::close_open_modal(&fiverr_tab).await?;
 
-    let mut fiverr_tab = browser.get_fiverr_tab()?;
-    log::info!("Fiverr tab title: {}", fiverr_tab.get_title()?);
+        let mut retries = 0;
+        loop {
+            let revisit = self.gigs_store.next_revisit().await?;
+            let revisiting = revisit.is_some();
+            let (target, gig_card) = match revisit {
+                Some(GigRevisit { target, card }) => {
+                    log::info!("Revisiting gig {}", card.url);
+                    (target, card)
+                }
+                None => {
+                    let Some(target) = self.target_cursor.current().cloned() else {
+                        log::info!("All scrape targets are exhausted");
+                        break;
+                    };
+                    log::info!("Scrape target: {target}");
+
+                    let gig_card = async {
+                        ErrorPageDetector::wait_until_cleared(&fiverr_tab).await?;
+                        let fiverr_nav = FiverrNav::new(&fiverr_tab);
+
+                        fiverr_nav.go_to_target(&target).await?;
+
+                        let progress = self.gigs_store.scrape_progress(&target).await?;
+
+                        let menu_item_page = MenuItemPage::new(
+                            &fiverr_tab,
+                            self.gigs_store.clone(),
+                            self.gig_filter.clone(),
+                        );
+                        menu_item_page.go_to_page(&progress).await?;
+                        menu_item_page
+                            .go_to_new_gig(&target, &progress, &mut self.listed_cards)
+                            .await
+                    }
+                    .await;
+                    match gig_card {
+                        Ok(Some(gig_card)) => (target, gig_card),
+                        Ok(None) => {
+                            log::info!("Scrape target exhausted: {target}");
+                            self.target_cursor.mark_exhausted();
+                            continue;
+                        }
+                        // There is no gig to pass over yet.
+                        Err(e) => match next_session_action(&e, &mut retries) {
+                            ErrorAction::Retry => continue,
+                            ErrorAction::SwitchTarget => {
+                                log::error!("Leaving scrape target {target}: {e}");
+                                self.target_cursor.mark_exhausted();
+                                continue;
+                            }
+                            _ => return Err(e),
+                        },
+                    }
+                }
+            };
 
-    ModalCloser::close_open_modal(&fiverr_tab).await?;
+            let (page, gig_index) = (gig_card.page, gig_card.position);
+            let gig_url = gig_card.url.clone();
+            // Failures of setting up the tab are not the gig's fault, unlike those of scraping
+            // it, so they come with whether the gig is to blame.
+            let scraped: Result<Option<String>, (anyhow::Error, bool)> = async {
+                let media_source = async {
+                    if !revisiting {
+                        // close current tab since new tab is opened when a gig card is clicked.
 
-    loop {
-        while ErrorPageDetector::process(&fiverr_tab).await? {}
-        let fiverr_nav = FiverrNav::new(&fiverr_tab);
+                        fiverr_tab.close(true)?;
 
-        fiverr_nav.go_to("programming-tech", "business").await?;
-        // fiverr_nav.go_to("data", "text-analysis-nlp")?;
+                        sleep(Duration::from_secs(5)).await;
 
-        let last_scraped_page = gigs_store.last_scraped_page().await?;
+                        fiverr_tab = browser.get_fiverr_tab()?;
+                    }
 
-        let menu_item_page = MenuItemPage::new(&fiverr_tab, gigs_store.clone());
-        menu_item_page.go_to_page(last_scraped_page).await?;
-        let gig_page = menu_item_page.go_to_new_gig().await?;
+                    let captured_media = match self.media_capture {
+                        MediaCapture::Gallery => None,
+                        _ => Some(browser.capture_media(&fiverr_tab)?),
+                    };
+                    if revisiting {
+                        log::info!("Navigate to: {gig_url}");
+                        fiverr_tab.navigate_to(&gig_url)?;
+                        NavigationWaiter::wait(&fiverr_tab, "gig page")?;
+                        sleep(Duration::from_secs(PAGE_RELOAD_WAIT_SECS)).await;
+                    } else if captured_media.is_some() {
+                        // The gig page loaded before the tab could be found, so load it again.
+                        log::info!("Reload tab");
+                        fiverr_tab.reload(false, None)?;
+                        NavigationWaiter::wait(&fiverr_tab, "gig page")?;
+                        sleep(Duration::from_secs(PAGE_RELOAD_WAIT_SECS)).await;
+                    }
 
-        // close current tab since new tab is opened when a gig card is clicked.
+                    ErrorPageDetector::wait_until_cleared(&fiverr_tab).await?;
 
-        fiverr_tab.close(true)?;
+                    Result::<_, anyhow::Error>::Ok(match captured_media {
+                        None => GigMediaSource::Gallery,
+                        Some(captured_media) => GigMediaSource::Network {
+                            captured_media,
+                            gallery_fallback: self.media_capture
+                                == MediaCapture::NetworkWithGalleryFallback,
+                        },
+                    })
+                }
+                .await
+                .map_err(|e| (e, false))?;
+                let capturing_media = matches!(media_source, GigMediaSource::Network { .. });
+
+                let gig_page = GigPage::new(
+                    &fiverr_tab,
+                    target.clone(),
+                    gig_card,
+                    self.review_limit,
+                    media_source,
+                );
+                let gig_data = gig_page.scrape().await;
+                if capturing_media {
+                    browser
+                        .stop_capturing_media(&fiverr_tab)
+                        .map_err(|e| (e, false))?;
+                }
+                let mut gig_data = gig_data.map_err(|e| (e, true))?;
+                let visuals = self
+                    .resource_downloader
+                    .download_media_files(std::mem::take(&mut gig_data.visuals))
+                    .await;
+                let incomplete = match &gig_data.gallery_error {
+                    Some(error) => Some(format!("Gallery not collected: {error}")),
+                    None if !visuals.is_empty()
+                        && visuals.iter().all(|visual| visual.outcome.is_err()) =>
+                    {
+                        Some(format!("All {} downloads failed", visuals.len()))
+                    }
+                    None => None,
+                };
+                log::debug!("Gig URL: {}", gig_data.url);
+                self.gigs_store
+                    .save(gig_data, visuals, incomplete.is_none())
+                    .await
+                    .map_err(|e| (ScrapeError::StoreError(e).into(), false))?;
+                Ok(incomplete)
+            }
+            .await;
+            // A gig that failed without being quarantined is left where it is in the listing, so
+            // that it is scraped again.
+            let advance = match scraped {
+                Ok(incomplete) => {
+                    retries = 0;
+                    self.consecutive_failures = 0;
+                    // Count a partial scrape as a failure so the revisits of the gig end.
+                    if let Some(reason) = incomplete {
+                        log::warn!("Saved gig {gig_url} as incomplete: {reason}");
+                        let quarantined = self
+                            .gigs_store
+                            .record_failed_gig(
+                                &target,
+                                &gig_url,
+                                page,
+                                &anyhow!(reason),
+                                self.quarantine_after,
+                            )
+                            .await?;
+                        if quarantined {
+                            log::error!("Quarantining incomplete gig {gig_url}");
+                        }
+                    }
+                    true
+                }
+                Err((e, false)) => match next_session_action(&e, &mut retries) {
+                    ErrorAction::Retry => continue,
+                    ErrorAction::SwitchTarget if !revisiting => {
+                        log::error!("Leaving scrape target {target}: {e}");
+                        self.target_cursor.mark_exhausted();
+                        continue;
+                    }
+                    // A new session would revisit the same gig, so a gig that cannot be opened
+                    // is counted as failed until it is quarantined.
+                    action if revisiting && counts_against_gig(&e, action) => {
+                        let quarantined = self
+                            .gigs_store
+                            .record_failed_gig(&target, &gig_url, page, &e, self.quarantine_after)
+                            .await?;
+                        match quarantined {
+                            true => log::error!("Quarantining revisited gig {gig_url}: {e}"),
+                            false => log::error!("Could not open revisited gig {gig_url}: {e}"),
+                        }
+                        continue;
+                    }
+                    _ => return Err(e),
+                },
+                Err((e, true)) => {
+                    let action = next_action(&e, &mut retries);
+                    if action == ErrorAction::Retry {
+                        continue;
+                    }
+                    let caused_by_gig = counts_against_gig(&e, action);
+                    let quarantined = caused_by_gig
+                        && self
+                            .gigs_store
+                            .record_failed_gig(&target, &gig_url, page, &e, self.quarantine_after)
+                            .await?;
+                    if quarantined {
+                        log::error!("Quarantining gig {gig_url}: {e}");
+                    } else {
+                        match action {
+                            ErrorAction::Retry => unreachable!("retries continue the loop above"),
+                            ErrorAction::SkipGig => {
+                                log::error!("Skipping gig {page}/{gig_index}: {e}")
+                            }
+                            ErrorAction::SwitchTarget if !revisiting => {
+                                log::error!("Leaving scrape target {target}: {e}");
+                                self.target_cursor.mark_exhausted();
+                                continue;
+                            }
+                            ErrorAction::SwitchTarget => {
+                                log::error!("Skipping revisited gig {gig_url}: {e}")
+                            }
+                            // The failure is recorded against the gig, so a new session would
+                            // only fail on it again.
+                            ErrorAction::Stop if caused_by_gig => {
+                                log::error!("Skipping gig {page}/{gig_index}: {e}")
+                            }
+                            ErrorAction::Stop => return Err(e),
+                        }
+                    }
+                    quarantined || !caused_by_gig
+                }
+            };
 
-        sleep(Duration::from_secs(5)).await;
+            if revisiting || !advance {
+                continue;
+            }
+            self.gigs_store
+                .record_scraped_gig(&target, page, gig_index)
+                .await?;
+            self.target_cursor.advance();
+        }
 
-        fiverr_tab = browser.get_fiverr_tab()?;
-        while ErrorPageDetector::process(&fiverr_tab).await? {}
+        Ok(())
+    }
+}
 
-        let gig_page = GigPage::new(&fiverr_tab, gig_page);
-        let gig_data = gig_page.scrape().await?;
-        let visuals = resource_downloader
-            .download_media_files(gig_data.visuals)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::atomic::{AtomicU32, Ordering as AtomicOrdering};
+    use tokio::io::AsyncReadExt;
+
+    async fn memory_store() -> Result<ScrapedGigsStore> {
+        // Every connection to an in-memory database opens a database of its own.
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
             .await?;
-        log::debug!("Gig URL: {}", gig_data.url);
-        let gig_data = GigData {
-            url: gig_data.url,
-            title: gig_data.title,
-            description: gig_data.description,
-            page: gig_data.page,
+        migrate::MIGRATOR.run(&db).await?;
+        Ok(ScrapedGigsStore::new(db))
+    }
+
+    fn menu_target(category: &str, menu: &str) -> ScrapeTarget {
+        ScrapeTarget {
+            category: category.to_owned(),
+            menu: menu.to_owned(),
+            search_query: None,
+        }
+    }
+
+    fn cursor_order(cursor: &mut ScrapeTargetCursor, steps: usize) -> Vec<String> {
+        (0..steps)
+            .map(|_| {
+                let target = cursor.current().map(ToString::to_string);
+                cursor.advance();
+                target.unwrap_or_default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sequential_cursor_stays_on_target_until_exhausted() -> Result<()> {
+        let targets = vec![menu_target("a", "1"), menu_target("b", "2")];
+        let mut cursor = ScrapeTargetCursor::new(targets, TargetRotation::Sequential)?;
+        assert_eq!(cursor_order(&mut cursor, 3), ["a/1", "a/1", "a/1"]);
+
+        cursor.mark_exhausted();
+        assert_eq!(cursor_order(&mut cursor, 2), ["b/2", "b/2"]);
+
+        cursor.mark_exhausted();
+        assert!(cursor.current().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn round_robin_cursor_skips_exhausted_targets() -> Result<()> {
+        let targets = vec![
+            menu_target("a", "1"),
+            menu_target("b", "2"),
+            menu_target("c", "3"),
+        ];
+        let mut cursor = ScrapeTargetCursor::new(targets, TargetRotation::RoundRobin)?;
+        assert_eq!(cursor_order(&mut cursor, 4), ["a/1", "b/2", "c/3", "a/1"]);
+
+        // b/2 is current after a/1 was scraped.
+        assert_eq!(cursor.current().map(ToString::to_string).unwrap(), "b/2");
+        cursor.mark_exhausted();
+        assert_eq!(cursor_order(&mut cursor, 4), ["c/3", "a/1", "c/3", "a/1"]);
+
+        for target in ["c/3", "a/1"] {
+            assert_eq!(cursor.current().map(ToString::to_string).unwrap(), target);
+            cursor.mark_exhausted();
+        }
+        assert!(cursor.current().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn cursor_rejects_missing_or_invalid_targets() {
+        assert!(ScrapeTargetCursor::new(Vec::new(), TargetRotation::Sequential).is_err());
+        let targets = vec![menu_target("a", "")];
+        assert!(ScrapeTargetCursor::new(targets, TargetRotation::Sequential).is_err());
+    }
+
+    #[tokio::test]
+    async fn scrape_progress_resumes_after_last_gig() -> Result<()> {
+        let store = memory_store().await?;
+        let target = menu_target("graphics-design", "logo-design");
+        let progress = store.scrape_progress(&target).await?;
+        assert_eq!(
+            (
+                progress.last_page,
+                progress.last_gig_index,
+                progress.page_finished
+            ),
+            (1, None, false)
+        );
+
+        store.record_scraped_gig(&target, 2, 5).await?;
+        let progress = store.scrape_progress(&target).await?;
+        assert_eq!(
+            (
+                progress.last_page,
+                progress.last_gig_index,
+                progress.page_finished
+            ),
+            (2, Some(5), false)
+        );
+
+        store.finish_page(&target, 2).await?;
+        let progress = store.scrape_progress(&target).await?;
+        assert_eq!(
+            (
+                progress.last_page,
+                progress.last_gig_index,
+                progress.page_finished
+            ),
+            (2, Some(5), true)
+        );
+
+        // Progress is kept per target.
+        let other = ScrapeTarget {
+            search_query: Some("logo".to_owned()),
+            ..target
+        };
+        assert!(
+            store
+                .scrape_progress(&other)
+                .await?
+                .last_gig_index
+                .is_none()
+        );
+        Ok(())
+    }
+
+    fn cdn_response(url: &str, mime_type: &str, headers: serde_json::Value) -> Network::Response {
+        serde_json::from_value(serde_json::json!({
+            "url": url,
+            "mimeType": mime_type,
+            "headers": headers,
+            "securityState": "secure",
+        }))
+        .expect("valid response")
+    }
+
+    fn captured(response: &Network::Response, body: Option<&str>) -> Option<CapturedMedia> {
+        let fetch_body = || match body {
+            Some(body) => Ok(Network::GetResponseBodyReturnObject {
+                body: body.to_owned(),
+                base_64_encoded: true,
+            }),
+            None => panic!("body of {} fetched", response.url),
+        };
+        CapturedMedia::from_response(response, &fetch_body)
+    }
+
+    const GIG_IMAGE: &str = "https://fiverr-res.cloudinary.com/images/t_main1,q_auto,f_auto/gigs/312345678/original/0a1b2c3d4e5f/design-a-modern-logo.png";
+
+    #[test]
+    fn captures_gig_media_from_the_cdn() {
+        let image = cdn_response(
+            GIG_IMAGE,
+            "image/PNG",
+            serde_json::json!({"Content-Length": "52311"}),
+        );
+        let image = captured(&image, None).expect("gig image to be captured");
+        assert_eq!(image.mime_type, "image/png");
+        assert_eq!(image.byte_size, Some(52311));
+        assert_eq!(image.slide_type(), SlideType::Image);
+
+        // A range response tells the total size after the slash.
+        let video = cdn_response(
+            "https://fiverr-res.cloudinary.com/video/upload/t_gig_video/v1/videos/t_main1/0a1b2c3d4e5f.mp4",
+            "video/mp4",
+            serde_json::json!({"content-range": "bytes 0-1048575/7340032", "content-length": "1048576"}),
+        );
+        let video = captured(&video, None).expect("gig video to be captured");
+        assert_eq!(video.byte_size, Some(7340032));
+        assert_eq!(video.slide_type(), SlideType::Video);
+
+        // Chunked responses are sized by their body, except for videos.
+        let pdf = cdn_response(
+            "https://fiverr-res.cloudinary.com/raw/upload/v1/gigs/312345678/brochure.pdf",
+            "application/pdf",
+            serde_json::json!({}),
+        );
+        let pdf = captured(&pdf, Some("JVBERi0=")).expect("gig PDF to be captured");
+        assert_eq!(pdf.byte_size, Some(5));
+        assert_eq!(pdf.slide_type(), SlideType::Pdf);
+        let video = cdn_response(
+            "https://fiverr-res.cloudinary.com/video/upload/v1/videos/0a1b2c3d4e5f.mp4",
+            "video/mp4",
+            serde_json::json!({}),
+        );
+        assert_eq!(captured(&video, None).unwrap().byte_size, None);
+    }
+
+    #[test]
+    fn ignores_other_responses() {
+        let ignored = [
+            // Cards of other gigs shown on the gig page.
+            (
+                "https://fiverr-res.cloudinary.com/images/t_smartwm/t_gig_cards_web,q_auto,f_auto/gigs/398765432/original/ffeeddcc/logo.jpg",
+                "image/jpeg",
+            ),
+            // Images outside the gigs folder, such as seller avatars.
+            (
+                "https://fiverr-res.cloudinary.com/t_profile_original,q_auto,f_auto/attachments/profile/photo/abc123/avatar.jpg",
+                "image/jpeg",
+            ),
+            (
+                "https://fiverr-res.cloudinary.com/npm-assets/layout-service/favicon.svg",
+                "text/html",
+            ),
+            (
+                "https://www.fiverr.com/gigs/312345678/original/logo.png",
+                "image/png",
+            ),
+            ("not a url", "image/png"),
+        ];
+        for (url, mime_type) in ignored {
+            let response = cdn_response(url, mime_type, serde_json::json!({}));
+            assert!(captured(&response, None).is_none(), "{url} captured");
+        }
+    }
+
+    #[test]
+    fn keeps_the_largest_rendition_of_each_asset() {
+        assert_eq!(
+            CapturedMedia::asset_name_of(GIG_IMAGE),
+            "design-a-modern-logo"
+        );
+        assert_eq!(
+            CapturedMedia::asset_name_of(
+                "https://fiverr-res.cloudinary.com/video/upload/v1/videos/clip.mp4?autoplay=1"
+            ),
+            "clip"
+        );
+
+        let media = |url: &str, mime_type: &str, byte_size| CapturedMedia {
+            url: url.to_owned(),
+            mime_type: mime_type.to_owned(),
+            byte_size,
+        };
+        let thumbnail = GIG_IMAGE.replace("t_main1,q_auto,f_auto", "t_thumbnail,q_auto,f_auto");
+        let video = "https://fiverr-res.cloudinary.com/video/upload/v1/videos/clip.mp4";
+        let captured_media: CapturedMediaLog = Arc::new(Mutex::new(vec![
+            media(&thumbnail, "image/png", Some(4096)),
+            media(video, "video/mp4", None),
+            media(GIG_IMAGE, "image/png", Some(52311)),
+            media(&thumbnail, "image/png", Some(4096)),
+        ]));
+        let visuals = GigPage::get_captured_visuals(&captured_media);
+        let visuals: Vec<_> = visuals
+            .iter()
+            .map(|visual| (visual.url.as_str(), &visual.typ, visual.position))
+            .collect();
+        assert_eq!(
             visuals,
+            [
+                (GIG_IMAGE, &SlideType::Image, 0),
+                (video, &SlideType::Video, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn restart_backoff_doubles_up_to_the_cap() {
+        let config = SupervisorConfig {
+            max_consecutive_failures: 100,
+            initial_backoff_secs: 5,
+            max_backoff_secs: 600,
+        };
+        let backoffs = [
+            (1, 5),
+            (2, 10),
+            (3, 20),
+            (7, 320),
+            (8, 600),
+            (64, 600),
+            (99, 600),
+        ];
+        for (consecutive_failures, backoff) in backoffs {
+            assert_eq!(
+                restart_backoff(&config, consecutive_failures),
+                Some(backoff),
+                "after {consecutive_failures} failures"
+            );
+        }
+
+        // Doubling a large initial backoff saturates instead of overflowing.
+        let config = SupervisorConfig {
+            max_consecutive_failures: u32::MAX,
+            initial_backoff_secs: u64::MAX / 2,
+            max_backoff_secs: u64::MAX,
         };
-        gigs_store.save(gig_data).await?;
+        assert_eq!(restart_backoff(&config, 3), Some(u64::MAX));
+        assert_eq!(restart_backoff(&config, u32::MAX - 1), Some(u64::MAX));
     }
 
-    Ok(())
+    #[test]
+    fn restart_backoff_stops_after_too_many_failures() {
+        let config = SupervisorConfig {
+            max_consecutive_failures: 3,
+            ..Default::default()
+        };
+        let backoffs = [(1, Some(5)), (2, Some(10)), (3, None), (4, None)];
+        for (consecutive_failures, backoff) in backoffs {
+            assert_eq!(restart_backoff(&config, consecutive_failures), backoff);
+        }
+    }
+
+    /// Feeds the errors to the decision and returns the actions taken and the retry count left.
+    fn actions(
+        decide: fn(&anyhow::Error, &mut u32) -> ErrorAction,
+        errors: Vec<anyhow::Error>,
+    ) -> (Vec<ErrorAction>, u32) {
+        let mut retries = 0;
+        let actions = errors.iter().map(|e| decide(e, &mut retries)).collect();
+        (actions, retries)
+    }
+
+    fn timeouts(count: usize) -> Vec<anyhow::Error> {
+        (0..count)
+            .map(|_| {
+                ScrapeError::NavigationTimeout {
+                    page: "gig page".to_owned(),
+                    message: "timed out".to_owned(),
+                }
+                .into()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gig_steps_are_retried_then_skipped() {
+        use ErrorAction::{Retry, SkipGig, Stop, SwitchTarget};
+
+        // Passing over the gig starts the count over for the next one.
+        let (taken, retries) = actions(next_action, timeouts(5));
+        assert_eq!(taken, [Retry, Retry, Retry, SkipGig, Retry]);
+        assert_eq!(retries, 1);
+
+        let cases: Vec<(anyhow::Error, ErrorAction)> = vec![
+            (
+                headless_chrome::browser::tab::NoElementFound {}.into(),
+                SkipGig,
+            ),
+            (
+                ScrapeError::PaginationExhausted { page: 2 }.into(),
+                SwitchTarget,
+            ),
+            (anyhow!("unknown"), Stop),
+        ];
+        for (error, action) in cases {
+            // Any other action than a retry starts the count over.
+            let mut retries = 2;
+            assert_eq!(next_action(&error, &mut retries), action);
+            assert_eq!(retries, 0);
+        }
+    }
+
+    #[test]
+    fn session_steps_are_retried_then_stopped() {
+        use ErrorAction::{Retry, Stop, SwitchTarget};
+
+        let (taken, retries) = actions(next_session_action, timeouts(4));
+        assert_eq!(taken, [Retry, Retry, Retry, Stop]);
+        assert_eq!(retries, 0);
+
+        // No gig can be skipped, so a missing element is retried like a timeout.
+        let missing: Vec<anyhow::Error> = (0..4)
+            .map(|_| headless_chrome::browser::tab::NoElementFound {}.into())
+            .collect();
+        assert_eq!(
+            actions(next_session_action, missing).0,
+            [Retry, Retry, Retry, Stop]
+        );
+
+        let mut retries = 2;
+        let exhausted = ScrapeError::PaginationExhausted { page: 2 }.into();
+        assert_eq!(next_session_action(&exhausted, &mut retries), SwitchTarget);
+        assert_eq!(retries, 0);
+    }
+
+    #[test]
+    fn finds_the_current_gallery_slide() {
+        let classes = |classes: &[&str]| -> Vec<String> {
+            classes.iter().map(|class| class.to_string()).collect()
+        };
+        assert_eq!(
+            GigPage::current_index(&classes(&["slideshow-slide", "slideshow-slide current"])),
+            Some(1)
+        );
+        assert_eq!(
+            GigPage::current_index(&classes(&[
+                "slideshow-slide current-video",
+                "slideshow-slide"
+            ])),
+            None
+        );
+        assert_eq!(GigPage::current_index(&[]), None);
+    }
+
+    async fn local_downloader(root: &Path) -> Result<ResourceDownloader> {
+        let staging_dir = root.join(media_store::STAGING_DIR);
+        fs::create_dir_all(&staging_dir).await?;
+        Ok(ResourceDownloader {
+            staging_dir,
+            media_store: Arc::new(media_store::LocalMediaStore::new(root).await?),
+            video_processor: None,
+            client: reqwest::Client::new(),
+            concurrency: 1,
+            max_file_size: None,
+            max_attempts: 3,
+            retry_delay: Duration::from_millis(1),
+        })
+    }
+
+    async fn store_file(downloader: &ResourceDownloader, contents: &[u8]) -> Result<String> {
+        let sha256 = hex::encode(Sha256::digest(contents));
+        let part_path = downloader.staging_dir.join(format!("{sha256}.part"));
+        fs::write(&part_path, contents).await?;
+        let key = ResourceDownloader::content_key(&sha256, "jpg");
+        Ok(downloader.media_store.put(&part_path, &key).await?.uri)
+    }
+
+    #[test]
+    fn content_keys_are_sharded_by_hash() {
+        let sha256 = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        assert_eq!(
+            ResourceDownloader::content_key(sha256, "png"),
+            format!("9f/86/{sha256}.png")
+        );
+    }
+
+    #[tokio::test]
+    async fn garbage_collection_keeps_referenced_and_staged_files() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("media-gc-{}", Uuid::new_v4()));
+        let downloader = local_downloader(&root).await?;
+        let kept = store_file(&downloader, b"referenced").await?;
+        let garbage = store_file(&downloader, b"unreferenced").await?;
+        let staged = downloader.staging_dir.join("in-flight.part");
+        fs::write(&staged, b"downloading").await?;
+        let sha256 = hex::encode(Sha256::digest(b"referenced"));
+        assert!(kept.ends_with(&format!("/{}/{}/{sha256}.jpg", &sha256[..2], &sha256[2..4])));
+        let referenced = HashSet::from([kept.clone()]);
+
+        assert_eq!(downloader.collect_garbage(&referenced, true).await?, 1);
+        assert!(fs::try_exists(media_store::file_path(&garbage)?).await?);
+
+        assert_eq!(downloader.collect_garbage(&referenced, false).await?, 1);
+        assert!(!fs::try_exists(media_store::file_path(&garbage)?).await?);
+        assert!(fs::try_exists(media_store::file_path(&kept)?).await?);
+        assert!(fs::try_exists(&staged).await?);
+        assert_eq!(downloader.collect_garbage(&referenced, false).await?, 0);
+
+        fs::remove_dir_all(&root).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn garbage_collection_refuses_a_store_without_referenced_files() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("media-gc-{}", Uuid::new_v4()));
+        let downloader = local_downloader(&root).await?;
+        let stored = store_file(&downloader, b"stored").await?;
+        let elsewhere = root.with_extension("other").join("ab/cd/elsewhere.jpg");
+        let referenced = HashSet::from([media_store::file_uri(&elsewhere)?]);
+
+        assert!(
+            downloader
+                .collect_garbage(&referenced, false)
+                .await
+                .is_err()
+        );
+        assert!(fs::try_exists(media_store::file_path(&stored)?).await?);
+
+        fs::remove_dir_all(&root).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn download_retry_delay_doubles_up_to_the_cap() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("media-retry-{}", Uuid::new_v4()));
+        let mut downloader = local_downloader(&root).await?;
+        downloader.retry_delay = Duration::from_secs(1);
+        let delays = [
+            (1, 1),
+            (2, 2),
+            (3, 4),
+            (9, 256),
+            (10, 300),
+            (33, 300),
+            (u32::MAX, 300),
+        ];
+        for (attempt, secs) in delays {
+            assert_eq!(
+                downloader.retry_delay_after(attempt),
+                Duration::from_secs(secs),
+                "after attempt {attempt}"
+            );
+        }
+
+        fs::remove_dir_all(&root).await?;
+        Ok(())
+    }
+
+    /// Serves every request with an empty response of the status, counting the requests.
+    async fn serve_status(status: u16) -> Result<(String, Arc<AtomicU32>)> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/gigs/logo.png", listener.local_addr()?);
+        let requests = Arc::new(AtomicU32::new(0));
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                counter.fetch_add(1, AtomicOrdering::SeqCst);
+                let mut request = [0; 1024];
+                let _ = socket.read(&mut request).await;
+                let response = format!(
+                    "HTTP/1.1 {status} Status\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        Ok((url, requests))
+    }
+
+    #[tokio::test]
+    async fn only_transient_statuses_are_retried() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("media-retry-{}", Uuid::new_v4()));
+        let downloader = local_downloader(&root).await?;
+        let attempts = [(500, 3), (503, 3), (429, 3), (404, 1), (403, 1), (400, 1)];
+        for (status, expected_attempts) in attempts {
+            let (url, requests) = serve_status(status).await?;
+            let failure = downloader
+                .download(&url)
+                .await
+                .expect_err("download to fail");
+            assert_eq!(failure.http_status, Some(status));
+            assert_eq!(
+                requests.load(AtomicOrdering::SeqCst),
+                expected_attempts,
+                "attempts for status {status}"
+            );
+        }
+
+        fs::remove_dir_all(&root).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn retried_timeouts_do_not_count_towards_quarantine() -> Result<()> {
+        let store = memory_store().await?;
+        let target = menu_target("graphics-design", "logo-design");
+        let url = "https://www.fiverr.com/seller/design-a-logo";
+
+        // Each visit of the gig times out until its retries run out, which is one failure.
+        let mut retries = 0;
+        let mut failures = 0;
+        for timeout in timeouts(2 * (MAX_STEP_RETRIES as usize + 1)) {
+            let action = next_action(&timeout, &mut retries);
+            if counts_against_gig(&timeout, action) {
+                failures += 1;
+                assert!(
+                    !store
+                        .record_failed_gig(&target, url, 1, &timeout, 3)
+                        .await?
+                );
+            }
+        }
+        assert_eq!(failures, 2);
+        assert!(!store.is_scraped(url).await?);
+
+        let missing = anyhow::Error::from(headless_chrome::browser::tab::NoElementFound {});
+        assert!(counts_against_gig(
+            &missing,
+            next_action(&missing, &mut retries)
+        ));
+        let lost = anyhow::Error::from(headless_chrome::browser::ConnectionClosed {});
+        assert!(!counts_against_gig(&lost, next_action(&lost, &mut retries)));
+        Ok(())
+    }
+
+    fn parsed_price(text: &str) -> Option<(f64, Option<String>)> {
+        PriceParser::parse(text).map(|price| (price.amount, price.currency))
+    }
+
+    #[test]
+    fn parses_card_prices() {
+        let usd = Some("USD".to_owned());
+        assert_eq!(parsed_price("From $1,250"), Some((1250.0, usd.clone())));
+        assert_eq!(parsed_price("From US$30"), Some((30.0, usd.clone())));
+        assert_eq!(parsed_price("US$45.50"), Some((45.5, usd)));
+        assert_eq!(
+            parsed_price("€1,200"),
+            Some((1200.0, Some("EUR".to_owned())))
+        );
+        assert_eq!(
+            parsed_price("From CHF 80"),
+            Some((80.0, Some("CHF".to_owned())))
+        );
+        assert_eq!(parsed_price("25"), Some((25.0, None)));
+        assert_eq!(parsed_price("From"), None);
+        assert_eq!(parsed_price(""), None);
+    }
+
+    #[test]
+    fn parses_card_review_counts() {
+        assert_eq!(ReviewCountParser::parse("(532)"), Some(532));
+        assert_eq!(ReviewCountParser::parse("(1,024)"), Some(1024));
+        assert_eq!(ReviewCountParser::parse("(1k+)"), Some(1000));
+        assert_eq!(ReviewCountParser::parse("(1.2K)"), Some(1200));
+        assert_eq!(ReviewCountParser::parse("(2m+)"), Some(2_000_000));
+        assert_eq!(ReviewCountParser::parse("()"), None);
+        assert_eq!(ReviewCountParser::parse("New"), None);
+    }
+
+    #[test]
+    fn finds_first_number() {
+        assert_eq!(NumberParser::first_u32("3 Days Delivery"), Some(3));
+        assert_eq!(
+            NumberParser::first_u32("Up to 14 days, 2 revisions"),
+            Some(14)
+        );
+        assert_eq!(NumberParser::first_u32("Unlimited Revisions"), None);
+        assert_eq!(NumberParser::first_u32(""), None);
+    }
+
+    #[tokio::test]
+    async fn failing_card_is_passed_over() -> Result<()> {
+        let store = memory_store().await?;
+        let target = menu_target("graphics-design", "logo-design");
+        store.record_scraped_gig(&target, 2, 1).await?;
+        let error = anyhow::Error::from(headless_chrome::browser::tab::NoElementFound {});
+        assert!(error::is_caused_by_gig(&error));
+
+        // The next walk of the page starts after the card instead of failing on it again.
+        store.skip_failed_card(&target, 2, 3, &error).await?;
+        let progress = store.scrape_progress(&target).await?;
+        assert_eq!((progress.last_page, progress.last_gig_index), (2, Some(3)));
+        assert!(store.failed_gigs().await?.is_empty());
+        assert!(store.next_revisit().await?.is_none());
+        Ok(())
+    }
+
+    fn scraped_gig(url: &str, target: &ScrapeTarget) -> GigData {
+        GigData {
+            url: url.to_owned(),
+            title: "I will design a modern logo".to_owned(),
+            description: "<p>Logos <b>fast</b></p>".to_owned(),
+            visuals: Vec::new(),
+            packages: Vec::new(),
+            seller: SellerData {
+                username: "logo_pro".to_owned(),
+                display_name: Some("Logo Pro".to_owned()),
+                level: None,
+                country: None,
+                member_since: None,
+                avg_response_time: None,
+                last_delivery: None,
+                languages: vec!["English".to_owned()],
+            },
+            reviews: Vec::new(),
+            tags: vec!["logo".to_owned(), "branding".to_owned()],
+            faq: vec![FaqData {
+                question: "Do you offer revisions?".to_owned(),
+                answer: Some("Yes".to_owned()),
+            }],
+            attributes: Vec::new(),
+            gallery_error: None,
+            target: target.clone(),
+            card: GigCardData {
+                url: url.to_owned(),
+                page: 2,
+                position: 4,
+                ..Default::default()
+            },
+        }
+    }
+
+    async fn count(store: &ScrapedGigsStore, table: &str) -> Result<i64> {
+        let query = format!("SELECT COUNT(*) FROM {table}");
+        Ok(sqlx::query_scalar(&query).fetch_one(&store.db).await?)
+    }
+
+    #[tokio::test]
+    async fn failed_save_leaves_no_gig() -> Result<()> {
+        let store = memory_store().await?;
+        sqlx::raw_sql(
+            "CREATE TRIGGER fail_faq BEFORE INSERT ON gig_faq
+            BEGIN SELECT RAISE(ABORT, 'FAQ not saved'); END",
+        )
+        .execute(&store.db)
+        .await?;
+        let url = "https://www.fiverr.com/logo_pro/design-a-modern-logo";
+        let gig = scraped_gig(url, &menu_target("graphics-design", "logo-design"));
+
+        assert!(store.save(gig, Vec::new(), true).await.is_err());
+        for table in ["gigs", "sellers", "gig_tags", "gig_faq"] {
+            assert_eq!(count(&store, table).await?, 0, "rows left in {table}");
+        }
+        assert!(!store.is_scraped(url).await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn saves_gig_without_visuals() -> Result<()> {
+        let store = memory_store().await?;
+        let url = "https://www.fiverr.com/logo_pro/design-a-modern-logo";
+        let gig = scraped_gig(url, &menu_target("graphics-design", "logo-design"));
+
+        store.save(gig, Vec::new(), true).await?;
+        assert!(store.is_scraped(url).await?);
+        assert_eq!(count(&store, "visuals").await?, 0);
+        assert_eq!(count(&store, "gig_tags").await?, 2);
+        assert!(store.next_revisit().await?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn incomplete_gig_is_revisited_until_saved_complete() -> Result<()> {
+        let store = memory_store().await?;
+        let target = ScrapeTarget {
+            category: String::new(),
+            menu: String::new(),
+            search_query: Some("logo design".to_owned()),
+        };
+        let url = "https://www.fiverr.com/logo_pro/design-a-modern-logo";
+
+        store
+            .save(scraped_gig(url, &target), Vec::new(), false)
+            .await?;
+        let error = anyhow!("Gallery not collected");
+        assert!(!store.record_failed_gig(&target, url, 2, &error, 3).await?);
+        assert!(!store.is_scraped(url).await?);
+        let revisit = store
+            .next_revisit()
+            .await?
+            .expect("incomplete gig to revisit");
+        assert_eq!(revisit.card.url, url);
+        assert_eq!(revisit.card.page, 2);
+        assert_eq!(revisit.target.to_string(), "search:logo design");
+
+        // The gig page redirects to a renamed slug; the gig stays keyed by its card.
+        let mut gig = scraped_gig(url, &target);
+        gig.url = "https://www.fiverr.com/logo_pro/design-a-minimalist-logo".to_owned();
+        store.save(gig, Vec::new(), true).await?;
+        assert!(store.is_scraped(url).await?);
+        assert!(store.next_revisit().await?.is_none());
+        assert!(store.failed_gigs().await?.is_empty());
+        assert_eq!(count(&store, "gigs").await?, 1);
+        Ok(())
+    }
+
+    fn review(reviewer: &str, text: &str, rating: f64) -> ReviewData {
+        ReviewData {
+            reviewer: Some(reviewer.to_owned()),
+            country: Some("Germany".to_owned()),
+            rating: Some(rating),
+            date: Some("1 week ago".to_owned()),
+            text: text.to_owned(),
+            price_range: None,
+            duration: None,
+            seller_response: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn rescraped_reviews_are_updated_in_place() -> Result<()> {
+        let store = memory_store().await?;
+        let target = menu_target("graphics-design", "logo-design");
+        let url = "https://www.fiverr.com/logo_pro/design-a-modern-logo";
+
+        let mut gig = scraped_gig(url, &target);
+        gig.reviews = vec![
+            review("anna", "Great work!", 5.0),
+            review("anna", "Great work!", 5.0),
+            review("ben", "Fast delivery", 4.0),
+        ];
+        store.save(gig, Vec::new(), true).await?;
+
+        // The page now shows a third identical review by anna, an answered and re-rated review
+        // by ben, and a new reviewer.
+        let mut gig = scraped_gig(url, &target);
+        let mut answered = review("ben", "Fast delivery", 5.0);
+        answered.date = Some("2 weeks ago".to_owned());
+        answered.seller_response = Some("Thank you!".to_owned());
+        gig.reviews = vec![
+            review("anna", "Great work!", 5.0),
+            answered,
+            review("anna", "Great work!", 5.0),
+            review("anna", "Great work!", 5.0),
+            review("carl", "Would order again", 5.0),
+        ];
+        store.save(gig, Vec::new(), true).await?;
+
+        let reviews: Vec<(String, f64, Option<String>, String)> = sqlx::query_as(
+            "SELECT reviewer, rating, seller_response, review_date FROM reviews
+            ORDER BY reviewer, first_seen_at",
+        )
+        .fetch_all(&store.db)
+        .await?;
+        let reviewers: Vec<_> = reviews.iter().map(|review| review.0.as_str()).collect();
+        assert_eq!(reviewers, ["anna", "anna", "anna", "ben", "carl"]);
+        let ben = &reviews[3];
+        assert_eq!(ben.1, 5.0);
+        assert_eq!(ben.2.as_deref(), Some("Thank you!"));
+        assert_eq!(ben.3, "2 weeks ago");
+
+        let first = review("anna", "Great work!", 5.0);
+        assert_ne!(
+            ScrapedGigsStore::review_key(&first, 0),
+            ScrapedGigsStore::review_key(&first, 1)
+        );
+        let mut elsewhere = review("anna", "Great work!", 5.0);
+        elsewhere.country = Some("Austria".to_owned());
+        assert_ne!(
+            ScrapedGigsStore::review_key(&first, 0),
+            ScrapedGigsStore::review_key(&elsewhere, 0)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn requeued_gig_is_scraped_again() -> Result<()> {
+        let store = memory_store().await?;
+        let target = menu_target("graphics-design", "logo-design");
+        let url = "https://www.fiverr.com/seller/design-a-logo";
+        let error = anyhow!("Gig page did not load");
+
+        assert!(!store.record_failed_gig(&target, url, 2, &error, 2).await?);
+        let revisit = store.next_revisit().await?.expect("failed gig to revisit");
+        assert_eq!(revisit.card.url, url);
+        assert_eq!(revisit.card.page, 2);
+        assert_eq!(revisit.target.to_string(), "graphics-design/logo-design");
+
+        assert!(store.record_failed_gig(&target, url, 2, &error, 2).await?);
+        assert!(store.is_scraped(url).await?);
+        assert!(store.next_revisit().await?.is_none());
+
+        assert_eq!(store.requeue_failed_gigs(Some(url)).await?, 1);
+        assert!(!store.is_scraped(url).await?);
+        let revisit = store
+            .next_revisit()
+            .await?
+            .expect("requeued gig to revisit");
+        assert_eq!(revisit.card.url, url);
+        Ok(())
+    }
 }
@@ -0,0 +1,356 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use object_store::{
+    ObjectStore, aws::AmazonS3Builder, buffered::BufWriter, path::Path as ObjectPath,
+};
+use tokio::{fs, io::AsyncWriteExt};
+use url::Url;
+
+use crate::app_config::{MediaStoreConfig, S3StoreConfig};
+
+/// Where downloaded media files are kept. Stored files are referred to by a storage URI: a
+/// `file://` URL of the absolute path for the local filesystem and `s3://<bucket>/<key>` for S3.
+#[async_trait]
+pub trait MediaStore: Send + Sync {
+    /// Moves the local file at `source` into the store under `key`. When a file with the key is
+    /// stored already, that file is kept and `source` is deleted.
+    async fn put(&self, source: &Path, key: &str) -> Result<StoredFile>;
+
+    async fn read(&self, uri: &str) -> Result<Vec<u8>>;
+
+    /// The URIs of all stored files.
+    async fn list(&self) -> Result<Vec<String>>;
+
+    async fn delete(&self, uri: &str) -> Result<()>;
+
+    /// The URI in the form [`Self::list`] returns, for comparing URIs saved at other times.
+    async fn canonical_uri(&self, uri: &str) -> Result<String> {
+        Ok(uri.to_owned())
+    }
+}
+
+/// The directory in `download_dir` where files are staged until they are put into the store. It
+/// is not part of the local store.
+pub const STAGING_DIR: &str = ".staging";
+
+pub struct StoredFile {
+    pub uri: String,
+    /// Whether a file with the same key was stored already.
+    pub existed: bool,
+}
+
+/// Creates the store selected in the config. The local store keeps files in `download_dir`.
+pub async fn from_config(
+    config: &MediaStoreConfig,
+    download_dir: &Path,
+) -> Result<Arc<dyn MediaStore>> {
+    Ok(match config {
+        MediaStoreConfig::Local => Arc::new(LocalMediaStore::new(download_dir).await?),
+        MediaStoreConfig::S3(config) => Arc::new(S3MediaStore::new(config)?),
+    })
+}
+
+pub struct LocalMediaStore {
+    root: PathBuf,
+}
+
+impl LocalMediaStore {
+    pub async fn new(root: &Path) -> Result<Self> {
+        fs::create_dir_all(root).await?;
+        Ok(Self {
+            root: fs::canonicalize(root).await?,
+        })
+    }
+
+    fn uri_of(path: &Path) -> Result<String> {
+        file_uri(path)
+    }
+
+    fn path_of(uri: &str) -> Result<PathBuf> {
+        file_path(uri)
+    }
+}
+
+/// The storage URI of a local file. Relative paths are taken relative to the working directory.
+pub fn file_uri(path: &Path) -> Result<String> {
+    let path = std::path::absolute(path)?;
+    let url = Url::from_file_path(&path)
+        .map_err(|()| anyhow!("Cannot make a URI of path {}", path.display()))?;
+    Ok(url.to_string())
+}
+
+/// The path of a local file from its storage URI. URIs stored before they were proper URLs hold
+/// a path relative to the working directory after `file://`.
+pub fn file_path(uri: &str) -> Result<PathBuf> {
+    if uri.starts_with("file:///")
+        && let Ok(path) = Url::parse(uri)?.to_file_path()
+    {
+        return Ok(path);
+    }
+    uri.strip_prefix("file://")
+        .map(PathBuf::from)
+        .ok_or(anyhow!("Not a local storage URI: {uri}"))
+}
+
+#[async_trait]
+impl MediaStore for LocalMediaStore {
+    async fn put(&self, source: &Path, key: &str) -> Result<StoredFile> {
+        let path = self.root.join(key);
+        let existed = fs::try_exists(&path).await?;
+        if existed {
+            fs::remove_file(source).await?;
+        } else {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).await?;
+            }
+            fs::rename(source, &path).await?;
+        }
+        Ok(StoredFile {
+            uri: Self::uri_of(&path)?,
+            existed,
+        })
+    }
+
+    async fn read(&self, uri: &str) -> Result<Vec<u8>> {
+        Ok(fs::read(Self::path_of(uri)?).await?)
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        let mut uris = Vec::new();
+        let mut dirs = vec![self.root.clone()];
+        while let Some(dir) = dirs.pop() {
+            let mut entries = fs::read_dir(&dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                if entry.file_type().await?.is_dir() {
+                    if path != self.root.join(STAGING_DIR) {
+                        dirs.push(path);
+                    }
+                } else {
+                    uris.push(Self::uri_of(&path)?);
+                }
+            }
+        }
+        Ok(uris)
+    }
+
+    /// Deletes the file along with the directories below the root that it leaves empty.
+    async fn delete(&self, uri: &str) -> Result<()> {
+        let path = Self::path_of(uri)?;
+        fs::remove_file(&path).await?;
+        for dir in path.ancestors().skip(1) {
+            if dir == self.root || fs::remove_dir(dir).await.is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves symlinks and relative paths, so a file is found however its path was written.
+    async fn canonical_uri(&self, uri: &str) -> Result<String> {
+        let path = std::path::absolute(Self::path_of(uri)?)?;
+        match fs::canonicalize(&path).await {
+            Ok(path) => Self::uri_of(&path),
+            Err(_) => Self::uri_of(&path),
+        }
+    }
+}
+
+/// Keeps files in a bucket of S3 or an S3-compatible service such as MinIO. Credentials not set
+/// in the config are taken from the usual `AWS_*` environment variables.
+pub struct S3MediaStore {
+    store: Arc<dyn ObjectStore>,
+    bucket: String,
+    prefix: String,
+}
+
+impl S3MediaStore {
+    pub fn new(config: &S3StoreConfig) -> Result<Self> {
+        let mut builder = AmazonS3Builder::from_env()
+            .with_bucket_name(&config.bucket)
+            .with_allow_http(config.allow_http);
+        if let Some(endpoint) = &config.endpoint {
+            builder = builder.with_endpoint(endpoint);
+        }
+        if let Some(region) = &config.region {
+            builder = builder.with_region(region);
+        }
+        if let Some(access_key_id) = &config.access_key_id {
+            builder = builder.with_access_key_id(access_key_id);
+        }
+        if let Some(secret_access_key) = &config.secret_access_key {
+            builder = builder.with_secret_access_key(secret_access_key);
+        }
+        let prefix = config.prefix.trim_matches('/');
+        Ok(Self {
+            store: Arc::new(builder.build()?),
+            bucket: config.bucket.clone(),
+            prefix: match prefix.is_empty() {
+                true => String::new(),
+                false => format!("{prefix}/"),
+            },
+        })
+    }
+
+    fn uri_of(&self, location: &ObjectPath) -> String {
+        format!("s3://{}/{location}", self.bucket)
+    }
+
+    fn location_of(&self, uri: &str) -> Result<ObjectPath> {
+        let key = uri
+            .strip_prefix("s3://")
+            .and_then(|uri| uri.strip_prefix(self.bucket.as_str()))
+            .and_then(|uri| uri.strip_prefix('/'))
+            .ok_or(anyhow!(
+                "Not a storage URI of bucket {}: {uri}",
+                self.bucket
+            ))?;
+        Ok(ObjectPath::parse(key)?)
+    }
+}
+
+#[async_trait]
+impl MediaStore for S3MediaStore {
+    async fn put(&self, source: &Path, key: &str) -> Result<StoredFile> {
+        let location = ObjectPath::parse(format!("{}{key}", self.prefix))?;
+        let existed = match self.store.head(&location).await {
+            Ok(_) => true,
+            Err(object_store::Error::NotFound { .. }) => false,
+            Err(e) => return Err(e.into()),
+        };
+        if !existed {
+            // Uploads large files in parts instead of reading them into memory.
+            let mut writer = BufWriter::new(self.store.clone(), location.clone());
+            let uploaded = async {
+                let mut file = fs::File::open(source).await?;
+                tokio::io::copy(&mut file, &mut writer).await?;
+                writer.shutdown().await?;
+                Result::<_, anyhow::Error>::Ok(())
+            }
+            .await;
+            if let Err(e) = uploaded {
+                // Otherwise the parts uploaded so far are kept (and billed) by the service.
+                if let Err(abort_error) = writer.abort().await {
+                    log::warn!("Could not abort the upload of {location}: {abort_error}");
+                }
+                return Err(e);
+            }
+        }
+        fs::remove_file(source).await?;
+        Ok(StoredFile {
+            uri: self.uri_of(&location),
+            existed,
+        })
+    }
+
+    async fn read(&self, uri: &str) -> Result<Vec<u8>> {
+        let object = self.store.get(&self.location_of(uri)?).await?;
+        Ok(object.bytes().await?.to_vec())
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        let prefix = ObjectPath::parse(&self.prefix)?;
+        let objects: Vec<_> = self.store.list(Some(&prefix)).try_collect().await?;
+        Ok(objects
+            .iter()
+            .map(|object| self.uri_of(&object.location))
+            .collect())
+    }
+
+    async fn delete(&self, uri: &str) -> Result<()> {
+        self.store.delete(&self.location_of(uri)?).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Puts two files under the same key, then reads, lists and deletes the stored file.
+    async fn round_trip(store: &dyn MediaStore, staging_dir: &Path) -> Result<String> {
+        fs::create_dir_all(staging_dir).await?;
+        let key = format!("ab/cd/{}.txt", uuid::Uuid::new_v4());
+        let source = staging_dir.join("first.part");
+        fs::write(&source, b"media").await?;
+        let stored = store.put(&source, &key).await?;
+        assert!(!stored.existed);
+        assert!(!fs::try_exists(&source).await?);
+
+        let duplicate = staging_dir.join("second.part");
+        fs::write(&duplicate, b"media").await?;
+        let stored_again = store.put(&duplicate, &key).await?;
+        assert!(stored_again.existed);
+        assert_eq!(stored_again.uri, stored.uri);
+        assert!(!fs::try_exists(&duplicate).await?);
+
+        assert_eq!(store.read(&stored.uri).await?, b"media");
+        assert!(store.list().await?.contains(&stored.uri));
+        store.delete(&stored.uri).await?;
+        assert!(!store.list().await?.contains(&stored.uri));
+        Ok(stored.uri)
+    }
+
+    #[tokio::test]
+    async fn local_store_round_trip() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("media-store-{}", uuid::Uuid::new_v4()));
+        let store = LocalMediaStore::new(&root).await?;
+        let uri = round_trip(&store, &root.join(STAGING_DIR)).await?;
+        // The staging directory is not part of the store.
+        fs::write(root.join(STAGING_DIR).join("left.part"), b"").await?;
+        assert!(store.list().await?.is_empty());
+
+        let url = Url::parse(&uri)?;
+        assert_eq!(url.scheme(), "file");
+        assert!(file_path(&uri)?.starts_with(fs::canonicalize(&root).await?));
+        // Emptied shard directories are removed along with the file.
+        assert!(!fs::try_exists(store.root.join("ab")).await?);
+
+        fs::remove_dir_all(&root).await?;
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_relative_file_uris() -> Result<()> {
+        assert_eq!(
+            file_path("file://downloads/ab/cd/x.jpg")?,
+            PathBuf::from("downloads/ab/cd/x.jpg")
+        );
+        assert_eq!(
+            file_path("file:///srv/media/a%20b.jpg")?,
+            PathBuf::from("/srv/media/a b.jpg")
+        );
+        assert!(file_path("s3://bucket/key").is_err());
+        Ok(())
+    }
+
+    /// Runs against a MinIO server, e.g. one started by `minio-test.sh`, which sets
+    /// the `MINIO_TEST_*` variables read here.
+    #[tokio::test]
+    #[ignore = "needs a MinIO server"]
+    async fn s3_store_round_trip() -> Result<()> {
+        let env = |name: &str| std::env::var(name).map_err(|_| anyhow!("{name} is not set"));
+        let store = S3MediaStore::new(&S3StoreConfig {
+            bucket: env("MINIO_TEST_BUCKET")?,
+            prefix: "media-store-test".to_owned(),
+            endpoint: Some(env("MINIO_TEST_ENDPOINT")?),
+            region: Some("us-east-1".to_owned()),
+            access_key_id: Some(env("MINIO_TEST_ACCESS_KEY_ID")?),
+            secret_access_key: Some(env("MINIO_TEST_SECRET_ACCESS_KEY")?),
+            allow_http: true,
+        })?;
+        let staging_dir =
+            std::env::temp_dir().join(format!("media-store-{}", uuid::Uuid::new_v4()));
+        let uri = round_trip(&store, &staging_dir).await?;
+        assert!(uri.starts_with(&format!("s3://{}/media-store-test/ab/cd/", store.bucket)));
+
+        fs::remove_dir_all(&staging_dir).await?;
+        Ok(())
+    }
+}
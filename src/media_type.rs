@@ -0,0 +1,138 @@
+/// The type of a downloaded media file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaType {
+    pub mime_type: Option<String>,
+    /// The file extension, without a leading dot.
+    pub extension: String,
+}
+
+/// The number of leading bytes of a file that are kept for sniffing its type.
+pub const SNIFF_LEN: usize = 8192;
+
+/// Detects the type of a file from the response `Content-Type`, falling back to the magic bytes at
+/// the start of the file and then to the extension in the URL path.
+pub fn detect(content_type: Option<&str>, head: &[u8], url_path: &str) -> MediaType {
+    let content_type = content_type
+        .and_then(|content_type| content_type.split(';').next())
+        .map(|mime_type| mime_type.trim().to_lowercase())
+        .filter(|mime_type| !is_generic(mime_type));
+
+    if let Some(mime_type) = content_type.as_deref()
+        && let Some(extension) = extension_of(mime_type)
+    {
+        return MediaType {
+            mime_type: Some(mime_type.to_owned()),
+            extension: extension.to_owned(),
+        };
+    }
+
+    if let Some(sniffed) = infer::get(head) {
+        return MediaType {
+            mime_type: Some(sniffed.mime_type().to_owned()),
+            extension: sniffed.extension().to_owned(),
+        };
+    }
+
+    let extension = std::path::Path::new(url_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| !ext.is_empty())
+        .map(str::to_lowercase)
+        .unwrap_or_else(|| "bin".to_owned());
+    MediaType {
+        mime_type: content_type,
+        extension,
+    }
+}
+
+/// Content types that servers send when they do not know the type of a file.
+fn is_generic(mime_type: &str) -> bool {
+    matches!(
+        mime_type,
+        "" | "application/octet-stream" | "binary/octet-stream" | "application/unknown"
+    )
+}
+
+fn extension_of(mime_type: &str) -> Option<&'static str> {
+    let extension = match mime_type {
+        "image/jpeg" | "image/jpg" | "image/pjpeg" => "jpg",
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/avif" => "avif",
+        "image/svg+xml" => "svg",
+        "video/mp4" => "mp4",
+        "video/webm" => "webm",
+        "video/quicktime" => "mov",
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/mp4" | "audio/x-m4a" => "m4a",
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "audio/ogg" => "ogg",
+        "application/pdf" => "pdf",
+        _ => return None,
+    };
+    Some(extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    const PDF: &[u8] = b"%PDF-1.7\n";
+
+    fn media_type(mime_type: Option<&str>, extension: &str) -> MediaType {
+        MediaType {
+            mime_type: mime_type.map(str::to_owned),
+            extension: extension.to_owned(),
+        }
+    }
+
+    #[test]
+    fn content_type_wins() {
+        assert_eq!(
+            detect(Some("image/jpeg"), PNG, "/a.png"),
+            media_type(Some("image/jpeg"), "jpg")
+        );
+        assert_eq!(
+            detect(Some("Video/MP4; codecs=avc1"), &[], "/a"),
+            media_type(Some("video/mp4"), "mp4")
+        );
+        assert_eq!(
+            detect(Some("image/pjpeg"), &[], "/a"),
+            media_type(Some("image/pjpeg"), "jpg")
+        );
+    }
+
+    #[test]
+    fn generic_content_type_is_sniffed() {
+        assert_eq!(
+            detect(Some("application/octet-stream"), PNG, "/a.bin"),
+            media_type(Some("image/png"), "png")
+        );
+        assert_eq!(
+            detect(None, PDF, "/a"),
+            media_type(Some("application/pdf"), "pdf")
+        );
+    }
+
+    #[test]
+    fn unknown_content_type_is_sniffed() {
+        assert_eq!(
+            detect(Some("image/x-unknown"), PNG, "/a"),
+            media_type(Some("image/png"), "png")
+        );
+    }
+
+    #[test]
+    fn url_extension_is_the_last_resort() {
+        assert_eq!(
+            detect(Some("image/x-unknown"), b"garbage", "/gigs/a.HEIC"),
+            media_type(Some("image/x-unknown"), "heic")
+        );
+        assert_eq!(
+            detect(Some("binary/octet-stream"), b"garbage", "/gigs/a"),
+            media_type(None, "bin")
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+use sqlx::{
+    SqlitePool,
+    migrate::{Migrate, Migrator},
+};
+
+use crate::media_store;
+
+/// The SQL files under `migrations/`, embedded at compile time.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// The migration creating the schema that databases predating the embedded migrations had
+/// applied by hand.
+const BASELINE_VERSION: i64 = 20250901155441;
+
+pub async fn up(db: &SqlitePool) -> Result<()> {
+    adopt_baseline(db).await?;
+    MIGRATOR.run(db).await?;
+    absolutize_file_uris(db).await?;
+    log::info!("Database migrations are up to date");
+    Ok(())
+}
+
+/// Records the baseline migration as applied on databases whose `gigs` table was created from
+/// its SQL out-of-band, so that only the later migrations run on them.
+async fn adopt_baseline(db: &SqlitePool) -> Result<()> {
+    let mut conn = db.acquire().await?;
+    conn.ensure_migrations_table().await?;
+    let applied_migrations = conn.list_applied_migrations().await?;
+    if applied_migrations
+        .iter()
+        .any(|applied| applied.version == BASELINE_VERSION)
+    {
+        return Ok(());
+    }
+
+    let (gigs_tables,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'gigs'")
+            .fetch_one(&mut *conn)
+            .await?;
+    if gigs_tables == 0 {
+        return Ok(());
+    }
+
+    let baseline = MIGRATOR
+        .iter()
+        .find(|migration| migration.version == BASELINE_VERSION)
+        .ok_or_else(|| anyhow!("Baseline migration {BASELINE_VERSION} is not embedded"))?;
+    sqlx::query(
+        "INSERT INTO _sqlx_migrations(version, description, success, checksum, execution_time)
+        VALUES($1, $2, TRUE, $3, 0)",
+    )
+    .bind(baseline.version)
+    .bind(&*baseline.description)
+    .bind(&*baseline.checksum)
+    .execute(&mut *conn)
+    .await?;
+    log::info!(
+        "Recorded baseline migration {BASELINE_VERSION} as applied to the existing gigs table"
+    );
+    Ok(())
+}
+
+/// Rewrites storage URIs of `file://` followed by a relative path, which the file paths were
+/// migrated to, into proper `file:///` URIs. The paths are relative to the working directory,
+/// which SQL migrations cannot resolve.
+async fn absolutize_file_uris(db: &SqlitePool) -> Result<()> {
+    let visuals = sqlx::query!(
+        r#"SELECT id AS "id!", storage_uri, poster_uri FROM visuals
+        WHERE (storage_uri LIKE 'file://%' AND storage_uri NOT LIKE 'file:///%')
+            OR (poster_uri LIKE 'file://%' AND poster_uri NOT LIKE 'file:///%')"#
+    )
+    .fetch_all(db)
+    .await?;
+    if visuals.is_empty() {
+        return Ok(());
+    }
+
+    let absolutize = |uri: Option<String>| -> Result<Option<String>> {
+        match uri {
+            Some(uri) if !uri.starts_with("file:///") => match uri.strip_prefix("file://") {
+                Some(path) => Ok(Some(media_store::file_uri(Path::new(path))?)),
+                None => Ok(Some(uri)),
+            },
+            uri => Ok(uri),
+        }
+    };
+    let mut tx = db.begin().await?;
+    for visual in &visuals {
+        let storage_uri = absolutize(visual.storage_uri.clone())?;
+        let poster_uri = absolutize(visual.poster_uri.clone())?;
+        sqlx::query!(
+            "UPDATE visuals SET storage_uri = $1, poster_uri = $2 WHERE id = $3",
+            storage_uri,
+            poster_uri,
+            visual.id
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    log::info!(
+        "Rewrote the relative storage URIs of {} visuals",
+        visuals.len()
+    );
+    Ok(())
+}
+
+pub async fn status(db: &SqlitePool) -> Result<()> {
+    let mut conn = db.acquire().await?;
+    conn.ensure_migrations_table().await?;
+    let applied_migrations = conn.list_applied_migrations().await?;
+
+    for migration in MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+    {
+        let applied = applied_migrations
+            .iter()
+            .find(|applied| applied.version == migration.version);
+        let status = match applied {
+            Some(applied) if applied.checksum != migration.checksum => {
+                "applied (checksum mismatch)"
+            }
+            Some(_) => "applied",
+            None => "pending",
+        };
+        println!(
+            "{}  {:<40}  {status}",
+            migration.version, migration.description
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn memory_db() -> Result<SqlitePool> {
+        // Every connection to an in-memory database opens a database of its own.
+        Ok(SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?)
+    }
+
+    #[tokio::test]
+    async fn adopts_baseline_applied_out_of_band() -> Result<()> {
+        let db = memory_db().await?;
+        sqlx::raw_sql(include_str!("../migrations/20250901155441_gigs.sql"))
+            .execute(&db)
+            .await?;
+        sqlx::query(
+            "INSERT INTO gigs(id, url, title, description, page) VALUES('1', 'u', 't', 'd', 1)",
+        )
+        .execute(&db)
+        .await?;
+
+        up(&db).await?;
+        // Running again finds everything applied.
+        up(&db).await?;
+
+        let mut conn = db.acquire().await?;
+        let applied_migrations = conn.list_applied_migrations().await?;
+        for migration in MIGRATOR.iter() {
+            let applied = applied_migrations
+                .iter()
+                .find(|applied| applied.version == migration.version)
+                .expect("migration to be applied");
+            assert_eq!(applied.checksum, migration.checksum);
+        }
+        let (gigs,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM gigs")
+            .fetch_one(&mut *conn)
+            .await?;
+        assert_eq!(gigs, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn applies_baseline_to_empty_database() -> Result<()> {
+        let db = memory_db().await?;
+        up(&db).await?;
+
+        let mut conn = db.acquire().await?;
+        let applied_migrations = conn.list_applied_migrations().await?;
+        assert_eq!(applied_migrations.len(), MIGRATOR.iter().count());
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use tokio::process::Command;
+
+use crate::app_config::VideoProcessingConfig;
+
+/// What ffprobe reports about a video.
+#[derive(Debug, Default)]
+pub struct VideoMetadata {
+    pub duration_secs: Option<f64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub codec: Option<String>,
+}
+
+/// Probes downloaded videos and extracts their poster frames with local ffprobe and ffmpeg
+/// binaries.
+pub struct VideoProcessor {
+    ffprobe: String,
+    ffmpeg: String,
+    poster_at_secs: f64,
+}
+
+impl VideoProcessor {
+    /// Returns `None` when processing is disabled or the binaries cannot be run.
+    pub async fn from_config(config: &VideoProcessingConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        for binary in [&config.ffprobe_path, &config.ffmpeg_path] {
+            let runs = Command::new(binary)
+                .arg("-version")
+                .output()
+                .await
+                .is_ok_and(|output| output.status.success());
+            if !runs {
+                log::warn!("Video processing is disabled, cannot run {binary}");
+                return None;
+            }
+        }
+        Some(Self {
+            ffprobe: config.ffprobe_path.clone(),
+            ffmpeg: config.ffmpeg_path.clone(),
+            poster_at_secs: config.poster_at_secs,
+        })
+    }
+
+    pub async fn probe(&self, video: &Path) -> Result<VideoMetadata> {
+        let output = Command::new(&self.ffprobe)
+            .args(["-v", "error", "-select_streams", "v:0"])
+            .args([
+                "-show_entries",
+                "stream=codec_name,width,height:format=duration",
+            ])
+            .args(["-of", "json"])
+            .arg(video)
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "ffprobe failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        parse_probe(&output.stdout)
+    }
+
+    /// Writes a JPEG of the frame at the configured offset to `poster`; videos shorter than the
+    /// offset get the frame from their middle.
+    pub async fn extract_poster(
+        &self,
+        video: &Path,
+        metadata: &VideoMetadata,
+        poster: &Path,
+    ) -> Result<()> {
+        let poster_at = self.poster_offset(metadata);
+        let output = Command::new(&self.ffmpeg)
+            .args(["-v", "error", "-y", "-ss", &poster_at.to_string(), "-i"])
+            .arg(video)
+            .args(["-frames:v", "1", "-f", "image2", "-c:v", "mjpeg"])
+            .arg(poster)
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "ffmpeg failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+
+    /// The second to take the poster frame at: the configured offset, or the middle of videos
+    /// shorter than that. Videos of unknown duration get the configured offset.
+    fn poster_offset(&self, metadata: &VideoMetadata) -> f64 {
+        match metadata.duration_secs {
+            Some(duration) if duration < self.poster_at_secs => duration / 2.0,
+            _ => self.poster_at_secs,
+        }
+    }
+}
+
+/// Reads the JSON output of ffprobe for the first video stream. Files without a video stream,
+/// such as audio-only ones, have no dimensions or codec.
+fn parse_probe(json: &[u8]) -> Result<VideoMetadata> {
+    let probe: Probe = serde_json::from_slice(json)?;
+    let stream = probe.streams.into_iter().next().unwrap_or_default();
+    Ok(VideoMetadata {
+        duration_secs: probe
+            .format
+            .and_then(|format| format.duration)
+            .and_then(|duration| duration.parse().ok()),
+        width: stream.width,
+        height: stream.height,
+        codec: stream.codec_name,
+    })
+}
+
+#[derive(Deserialize)]
+struct Probe {
+    #[serde(default)]
+    streams: Vec<ProbeStream>,
+    format: Option<ProbeFormat>,
+}
+
+#[derive(Default, Deserialize)]
+struct ProbeStream {
+    codec_name: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct ProbeFormat {
+    /// Seconds as a decimal string.
+    duration: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_video_probe() -> Result<()> {
+        let json = br#"{
+            "programs": [],
+            "streams": [{"codec_name": "h264", "width": 1280, "height": 720}],
+            "format": {"duration": "31.533333"}
+        }"#;
+        let metadata = parse_probe(json)?;
+        assert_eq!(metadata.duration_secs, Some(31.533333));
+        assert_eq!((metadata.width, metadata.height), (Some(1280), Some(720)));
+        assert_eq!(metadata.codec.as_deref(), Some("h264"));
+        Ok(())
+    }
+
+    #[test]
+    fn parses_probe_without_video_stream() -> Result<()> {
+        // Audio-only files have no stream selected by `v:0`.
+        let metadata = parse_probe(br#"{"streams": [], "format": {"duration": "12.0"}}"#)?;
+        assert_eq!(metadata.duration_secs, Some(12.0));
+        assert_eq!((metadata.width, metadata.height), (None, None));
+        assert_eq!(metadata.codec, None);
+
+        let metadata = parse_probe(br#"{"format": {"duration": "N/A"}}"#)?;
+        assert_eq!(metadata.duration_secs, None);
+        let metadata = parse_probe(b"{}")?;
+        assert_eq!(metadata.duration_secs, None);
+        assert!(parse_probe(b"ffprobe: not json").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn poster_offset_fits_the_video() {
+        let processor = VideoProcessor {
+            ffprobe: "ffprobe".to_owned(),
+            ffmpeg: "ffmpeg".to_owned(),
+            poster_at_secs: 3.0,
+        };
+        let offset = |duration_secs| {
+            processor.poster_offset(&VideoMetadata {
+                duration_secs,
+                ..Default::default()
+            })
+        };
+        assert_eq!(offset(Some(30.0)), 3.0);
+        assert_eq!(offset(Some(3.0)), 3.0);
+        assert_eq!(offset(Some(2.0)), 1.0);
+        assert_eq!(offset(Some(0.0)), 0.0);
+        assert_eq!(offset(None), 3.0);
+    }
+}